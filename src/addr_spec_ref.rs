@@ -0,0 +1,213 @@
+use std::{borrow::Cow, fmt};
+
+use super::{is_quoted, parser::Parser, write_addr_spec, AddrSpec, ParseError, ParserOptions};
+
+/// Borrowed address specification, i.e. a zero-copy version of [`AddrSpec`].
+///
+/// The local part and domain borrow from the input whenever no unescaping or
+/// normalization is needed, which avoids any allocation for the common case.
+/// Use [`AddrSpecRef::into_owned`] to convert it into an [`AddrSpec`].
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use addr_spec::AddrSpecRef;
+///
+/// let addr_spec = AddrSpecRef::parse("jdoe@example.com").unwrap();
+/// assert!(matches!(addr_spec.local_part_cow(), Cow::Borrowed("jdoe")));
+/// assert!(matches!(addr_spec.domain_cow(), Cow::Borrowed("example.com")));
+///
+/// let addr_spec = AddrSpecRef::parse(r#""j\"doe"@example.com"#).unwrap();
+/// assert!(matches!(addr_spec.local_part_cow(), Cow::Owned(_)));
+/// assert_eq!(addr_spec.to_string(), r#""j\"doe"@example.com"#);
+///
+/// assert_eq!(addr_spec.into_owned().local_part(), "j\"doe");
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct AddrSpecRef<'a> {
+    pub(crate) local_part: Cow<'a, str>,
+    pub(crate) domain: Cow<'a, str>,
+    pub(crate) literal: bool,
+}
+
+impl<'a> AddrSpecRef<'a> {
+    /// Parses an address specification using the default options.
+    #[inline]
+    pub fn parse(address: &'a str) -> Result<Self, ParseError> {
+        Parser::new(address).parse_ref()
+    }
+
+    /// Parses an address specification using the given options.
+    #[inline]
+    pub fn parse_with(address: &'a str, options: &ParserOptions) -> Result<Self, ParseError> {
+        Parser::with_options(address, options).parse_ref()
+    }
+
+    /// Returns the local part of the address.
+    #[inline]
+    pub fn local_part(&self) -> &str {
+        &self.local_part
+    }
+
+    /// Returns the local part of the address, borrowed from the input if
+    /// possible.
+    #[inline]
+    pub fn local_part_cow(&self) -> &Cow<'a, str> {
+        &self.local_part
+    }
+
+    /// Returns the domain of the address.
+    #[inline]
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// Returns the domain of the address, borrowed from the input if possible.
+    #[inline]
+    pub fn domain_cow(&self) -> &Cow<'a, str> {
+        &self.domain
+    }
+
+    /// Returns whether the local part is quoted.
+    #[inline]
+    pub fn is_quoted(&self) -> bool {
+        is_quoted(self.local_part())
+    }
+
+    /// Returns whether the domain is literal.
+    #[inline]
+    pub fn is_literal(&self) -> bool {
+        self.literal
+    }
+
+    /// Returns whether both the local part and domain borrow from the input.
+    #[inline]
+    pub fn is_borrowed(&self) -> bool {
+        matches!(
+            (&self.local_part, &self.domain),
+            (Cow::Borrowed(_), Cow::Borrowed(_))
+        )
+    }
+
+    /// Returns the local part and domain of the address.
+    #[inline]
+    pub fn into_parts(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        (self.local_part, self.domain)
+    }
+
+    /// Converts the address specification into an owned [`AddrSpec`].
+    ///
+    /// Parts that are already owned are moved without copying.
+    #[inline]
+    pub fn into_owned(self) -> AddrSpec {
+        AddrSpec {
+            local_part: self.local_part.into_owned(),
+            domain: self.domain.into_owned(),
+            literal: self.literal,
+        }
+    }
+}
+
+impl<'a> From<&'a AddrSpec> for AddrSpecRef<'a> {
+    #[inline]
+    fn from(addr_spec: &'a AddrSpec) -> Self {
+        Self {
+            local_part: Cow::Borrowed(addr_spec.local_part()),
+            domain: Cow::Borrowed(addr_spec.domain()),
+            literal: addr_spec.is_literal(),
+        }
+    }
+}
+
+impl From<AddrSpecRef<'_>> for AddrSpec {
+    #[inline]
+    fn from(addr_spec: AddrSpecRef<'_>) -> Self {
+        addr_spec.into_owned()
+    }
+}
+
+impl PartialEq<AddrSpec> for AddrSpecRef<'_> {
+    #[inline]
+    fn eq(&self, other: &AddrSpec) -> bool {
+        self.local_part() == other.local_part()
+            && self.domain() == other.domain()
+            && self.is_literal() == other.is_literal()
+    }
+}
+
+impl PartialEq<AddrSpecRef<'_>> for AddrSpec {
+    #[inline]
+    fn eq(&self, other: &AddrSpecRef<'_>) -> bool {
+        other == self
+    }
+}
+
+impl fmt::Display for AddrSpecRef<'_> {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_addr_spec(
+            formatter,
+            self.local_part(),
+            self.domain(),
+            self.is_literal(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_addr_spec_ref_borrows_dot_atoms() {
+        let addr_spec = AddrSpecRef::parse("john.doe@example.com").unwrap();
+        assert!(addr_spec.is_borrowed());
+        assert_eq!(addr_spec.local_part(), "john.doe");
+        assert_eq!(addr_spec.domain(), "example.com");
+    }
+
+    #[test]
+    fn test_addr_spec_ref_borrows_simple_quoted_strings() {
+        let addr_spec = AddrSpecRef::parse("\"john@doe\"@example.com").unwrap();
+        assert!(addr_spec.is_borrowed());
+        assert_eq!(addr_spec.local_part(), "john@doe");
+        assert_eq!(addr_spec.to_string(), "\"john@doe\"@example.com");
+    }
+
+    #[test]
+    fn test_addr_spec_ref_owns_unescaped_quoted_strings() {
+        let addr_spec = AddrSpecRef::parse("\"john\\\\doe\"@example.com").unwrap();
+        assert!(!addr_spec.is_borrowed());
+        assert_eq!(addr_spec.local_part(), "john\\doe");
+    }
+
+    #[cfg(feature = "normalization")]
+    #[test]
+    fn test_addr_spec_ref_owns_normalized_parts() {
+        let addr_spec = AddrSpecRef::parse("cafe\u{301}@caf\u{e9}.example").unwrap();
+        assert!(matches!(addr_spec.local_part_cow(), Cow::Owned(_)));
+        assert!(matches!(addr_spec.domain_cow(), Cow::Borrowed(_)));
+        assert_eq!(addr_spec.local_part(), "caf\u{e9}");
+    }
+
+    #[test]
+    fn test_addr_spec_ref_into_owned() {
+        let input = "\"jdoe\"@example.com";
+        let addr_spec = AddrSpecRef::parse(input).unwrap();
+        assert_eq!(addr_spec, input.parse::<AddrSpec>().unwrap());
+        assert_eq!(
+            addr_spec.into_owned(),
+            AddrSpec::new("jdoe", "example.com").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_addr_spec_ref_from_addr_spec() {
+        let addr_spec = AddrSpec::new("jdoe", "example.com").unwrap();
+        let addr_spec_ref = AddrSpecRef::from(&addr_spec);
+        assert!(addr_spec_ref.is_borrowed());
+        assert_eq!(addr_spec_ref.to_string(), addr_spec.to_string());
+    }
+}
@@ -0,0 +1,500 @@
+use std::{fmt, slice, str::FromStr, vec};
+
+use super::{
+    mailbox::write_display_name,
+    parser::{is_ascii_control_and_not_htab, Parser},
+    unicode, Mailbox, ParseError, ParserOptions,
+};
+
+/// Group as defined in [RFC
+/// 5322](https://tools.ietf.org/html/rfc5322#section-3.4), i.e. a display name
+/// with a possibly empty list of mailboxes.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+///
+/// use addr_spec::{Address, Group};
+///
+/// let Address::Group(group) = Address::from_str("Team: a@x.com, B <b@y.com>;").unwrap() else {
+///     panic!("expected a group");
+/// };
+/// assert_eq!(group.display_name(), "Team");
+/// assert_eq!(group.mailboxes().len(), 2);
+/// assert_eq!(group.to_string(), "Team: a@x.com, B <b@y.com>;");
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Group {
+    display_name: String,
+    mailboxes: Vec<Mailbox>,
+}
+
+impl Group {
+    /// Creates a new group. This will validate the display name and perform
+    /// NFC-normalization.
+    pub fn new<DisplayName>(
+        display_name: DisplayName,
+        mailboxes: Vec<Mailbox>,
+    ) -> Result<Self, ParseError>
+    where
+        DisplayName: AsRef<str>,
+    {
+        let display_name = display_name.as_ref();
+        if let Some((index, chr)) = display_name
+            .char_indices()
+            .find(|&(_, chr)| is_ascii_control_and_not_htab(chr))
+        {
+            return Err(ParseError::invalid_character(
+                chr,
+                "invalid character in display name",
+                index,
+                &[],
+            ));
+        }
+        Ok(Self {
+            display_name: unicode::normalize(display_name),
+            mailboxes,
+        })
+    }
+
+    #[inline]
+    pub(crate) fn from_parts(display_name: String, mailboxes: Vec<Mailbox>) -> Self {
+        Self {
+            display_name,
+            mailboxes,
+        }
+    }
+
+    /// Returns the display name of the group.
+    #[inline]
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    /// Returns the mailboxes of the group.
+    #[inline]
+    pub fn mailboxes(&self) -> &[Mailbox] {
+        &self.mailboxes
+    }
+
+    /// Returns the display name and mailboxes of the group.
+    #[inline]
+    pub fn into_parts(self) -> (String, Vec<Mailbox>) {
+        (self.display_name, self.mailboxes)
+    }
+}
+
+impl fmt::Display for Group {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_display_name(formatter, self.display_name())?;
+        formatter.write_str(":")?;
+        for (index, mailbox) in self.mailboxes.iter().enumerate() {
+            formatter.write_str(if index == 0 { " " } else { ", " })?;
+            mailbox.fmt(formatter)?;
+        }
+        formatter.write_str(";")
+    }
+}
+
+/// Address as defined in [RFC
+/// 5322](https://tools.ietf.org/html/rfc5322#section-3.4), i.e. either a
+/// mailbox or a group of mailboxes.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Address {
+    /// A single mailbox, e.g. `John Doe <jdoe@machine.example>`.
+    Mailbox(Mailbox),
+    /// A named group of mailboxes, e.g. `Friends: jdoe@machine.example;`.
+    Group(Group),
+}
+
+impl Address {
+    /// Parses an address using the given options instead of the defaults
+    /// determined by the enabled features.
+    #[inline]
+    pub fn parse_with(address: &str, options: &ParserOptions) -> Result<Self, ParseError> {
+        Parser::with_options(address, options).parse_address()
+    }
+
+    /// Returns the mailboxes of the address, i.e. the mailbox itself or the
+    /// mailboxes of the group.
+    #[inline]
+    pub fn mailboxes(&self) -> &[Mailbox] {
+        match self {
+            Self::Mailbox(mailbox) => slice::from_ref(mailbox),
+            Self::Group(group) => group.mailboxes(),
+        }
+    }
+}
+
+impl From<Mailbox> for Address {
+    #[inline]
+    fn from(mailbox: Mailbox) -> Self {
+        Self::Mailbox(mailbox)
+    }
+}
+
+impl From<Group> for Address {
+    #[inline]
+    fn from(group: Group) -> Self {
+        Self::Group(group)
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mailbox(mailbox) => mailbox.fmt(formatter),
+            Self::Group(group) => group.fmt(formatter),
+        }
+    }
+}
+
+impl FromStr for Address {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(address, &ParserOptions::new())
+    }
+}
+
+/// Address list as defined in [RFC
+/// 5322](https://tools.ietf.org/html/rfc5322#section-3.4), e.g. the value of a
+/// `To` or `Cc` header field.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+///
+/// use addr_spec::AddressList;
+///
+/// let list = AddressList::from_str(r#""Doe, John" <jdoe@example.com>, Team: a@x.com;"#).unwrap();
+/// assert_eq!(list.addresses().len(), 2);
+/// assert_eq!(
+///     list.mailboxes()
+///         .map(|mailbox| mailbox.addr_spec().to_string())
+///         .collect::<Vec<_>>(),
+///     ["jdoe@example.com", "a@x.com"]
+/// );
+/// ```
+///
+/// Invalid entries can be reported individually:
+///
+/// ```
+/// use addr_spec::{AddressList, ParserOptions};
+///
+/// let entries = AddressList::parse_entries("a@x.com, b@@y.com, c@z.com", &ParserOptions::default());
+/// assert_eq!(entries.len(), 3);
+/// assert!(entries[0].is_ok());
+/// assert_eq!(entries[1].as_ref().unwrap_err().index(), 11);
+/// assert!(entries[2].is_ok());
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct AddressList {
+    addresses: Vec<Address>,
+}
+
+impl AddressList {
+    /// Parses an address list using the given options instead of the defaults
+    /// determined by the enabled features. Fails on the first invalid entry.
+    pub fn parse_with(list: &str, options: &ParserOptions) -> Result<Self, ParseError> {
+        Self::parse_entries(list, options).into_iter().collect()
+    }
+
+    /// Parses each entry of an address list using the given options.
+    ///
+    /// Parsing continues after invalid entries, so this returns one result per
+    /// entry. Error indices are relative to the whole list.
+    #[inline]
+    pub fn parse_entries(list: &str, options: &ParserOptions) -> Vec<Result<Address, ParseError>> {
+        Parser::with_options(list, options).parse_address_list()
+    }
+
+    /// Returns the addresses of the list.
+    #[inline]
+    pub fn addresses(&self) -> &[Address] {
+        &self.addresses
+    }
+
+    /// Returns all mailboxes of the list, including the members of groups.
+    #[inline]
+    pub fn mailboxes(&self) -> impl Iterator<Item = &Mailbox> {
+        self.addresses.iter().flat_map(Address::mailboxes)
+    }
+
+    /// Returns the addresses of the list.
+    #[inline]
+    pub fn into_addresses(self) -> Vec<Address> {
+        self.addresses
+    }
+}
+
+impl From<Vec<Address>> for AddressList {
+    #[inline]
+    fn from(addresses: Vec<Address>) -> Self {
+        Self { addresses }
+    }
+}
+
+impl FromIterator<Address> for AddressList {
+    #[inline]
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Address>,
+    {
+        Self {
+            addresses: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for AddressList {
+    type Item = Address;
+    type IntoIter = vec::IntoIter<Address>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.addresses.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a AddressList {
+    type Item = &'a Address;
+    type IntoIter = slice::Iter<'a, Address>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.addresses.iter()
+    }
+}
+
+impl fmt::Display for AddressList {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, address) in self.addresses.iter().enumerate() {
+            if index != 0 {
+                formatter.write_str(", ")?;
+            }
+            address.fmt(formatter)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for AddressList {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(list: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(list, &ParserOptions::new())
+    }
+}
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "serde")]
+impl Serialize for AddressList {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for AddressList {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParseErrorKind, Token};
+
+    #[test]
+    fn test_address_list_from_str() {
+        let list =
+            AddressList::from_str("jdoe@machine.example, John Doe <jdoe@example.com>").unwrap();
+        assert_eq!(
+            list.addresses(),
+            [
+                Address::Mailbox(Mailbox::from_str("jdoe@machine.example").unwrap()),
+                Address::Mailbox(Mailbox::from_str("John Doe <jdoe@example.com>").unwrap()),
+            ]
+        );
+        assert_eq!(
+            list.to_string(),
+            "jdoe@machine.example, John Doe <jdoe@example.com>"
+        );
+    }
+
+    #[test]
+    fn test_address_list_from_str_with_comma_in_display_name() {
+        let list = AddressList::from_str(
+            "\"Doe, John\" <jdoe@example.com>, \"Doe, Jane\" <jane@example.com>",
+        )
+        .unwrap();
+        assert_eq!(
+            list.mailboxes()
+                .map(|mailbox| mailbox.display_name().unwrap())
+                .collect::<Vec<_>>(),
+            ["Doe, John", "Doe, Jane"]
+        );
+    }
+
+    #[test]
+    fn test_address_list_from_str_with_empty_group() {
+        let list = AddressList::from_str("Undisclosed recipients:;").unwrap();
+        let [Address::Group(group)] = list.addresses() else {
+            panic!("expected a group");
+        };
+        assert_eq!(group.display_name(), "Undisclosed recipients");
+        assert!(group.mailboxes().is_empty());
+        assert_eq!(list.to_string(), "Undisclosed recipients:;");
+    }
+
+    #[test]
+    fn test_address_list_from_str_with_group() {
+        let list = AddressList::from_str("Team: a@x.com, b@y.com;, c@z.com").unwrap();
+        let [Address::Group(group), Address::Mailbox(mailbox)] = list.addresses() else {
+            panic!("expected a group and a mailbox");
+        };
+        assert_eq!(group.display_name(), "Team");
+        assert_eq!(
+            group
+                .mailboxes()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["a@x.com", "b@y.com"]
+        );
+        assert_eq!(mailbox.to_string(), "c@z.com");
+        assert_eq!(list.to_string(), "Team: a@x.com, b@y.com;, c@z.com");
+    }
+
+    #[test]
+    fn test_address_list_from_str_with_quoted_group_name() {
+        let list = AddressList::from_str("\"Team: A\": a@x.com;").unwrap();
+        let [Address::Group(group)] = list.addresses() else {
+            panic!("expected a group");
+        };
+        assert_eq!(group.display_name(), "Team: A");
+        assert_eq!(list.to_string(), "\"Team: A\": a@x.com;");
+    }
+
+    #[test]
+    fn test_address_list_from_str_with_invalid_entry() {
+        assert_eq!(
+            AddressList::from_str("a@x.com, b@@y.com").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::EmptyLabel,
+                "empty label in domain",
+                11..11,
+                &[Token::Atext]
+            )
+        );
+    }
+
+    #[test]
+    fn test_address_list_from_str_with_trailing_comma() {
+        assert_eq!(
+            AddressList::from_str("a@x.com,").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::EmptyLabel,
+                "empty label in local part",
+                8..8,
+                &[Token::Atext]
+            )
+        );
+    }
+
+    #[test]
+    fn test_address_list_from_str_with_unterminated_group() {
+        assert_eq!(
+            AddressList::from_str("Team: a@x.com").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::UnexpectedEnd,
+                "expected ',' or ';' in group",
+                13..13,
+                &[Token::Char(','), Token::Char(';')]
+            )
+        );
+    }
+
+    #[test]
+    fn test_address_list_parse_entries() {
+        let entries = AddressList::parse_entries(
+            "a@x.com, \"Doe, John\" <jdoe@@example.com>, Team: b@y.com, c@@z.com;, d@w.com",
+            &ParserOptions::new(),
+        );
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].as_ref().unwrap().to_string(), "a@x.com");
+        assert_eq!(
+            entries[1].as_ref().unwrap_err(),
+            &ParseError::new(
+                ParseErrorKind::EmptyLabel,
+                "empty label in domain",
+                27..27,
+                &[Token::Atext]
+            )
+        );
+        assert_eq!(
+            entries[2].as_ref().unwrap_err(),
+            &ParseError::new(
+                ParseErrorKind::EmptyLabel,
+                "empty label in domain",
+                59..59,
+                &[Token::Atext]
+            )
+        );
+        assert_eq!(entries[3].as_ref().unwrap().to_string(), "d@w.com");
+    }
+
+    #[test]
+    fn test_address_from_str() {
+        assert_eq!(
+            Address::from_str("John Doe <jdoe@example.com>").unwrap(),
+            Address::Mailbox(Mailbox::from_str("John Doe <jdoe@example.com>").unwrap())
+        );
+        assert_eq!(
+            Address::from_str("a@x.com, b@y.com").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::TrailingInput,
+                "expected end of address",
+                7..16,
+                &[Token::End]
+            )
+        );
+    }
+
+    #[test]
+    fn test_group_new() {
+        let group = Group::new(
+            "Doe, Family",
+            vec![Mailbox::from_str("jdoe@example.com").unwrap()],
+        )
+        .unwrap();
+        assert_eq!(group.to_string(), "\"Doe, Family\": jdoe@example.com;");
+        assert_eq!(
+            Group::new("Doe\nFamily", Vec::new()).unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: '\n' },
+                "invalid character in display name",
+                3..4,
+                &[]
+            )
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::{iter, str};
+
+use super::{ParseError, ParseErrorKind, ParserOptions};
+
+/// Legacy single-byte charset used to decode byte input that is not valid
+/// UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{AddrSpec, Charset, ParserOptions};
+///
+/// let mut options = ParserOptions::default();
+/// options.fallback_charset = Some(Charset::Latin1);
+///
+/// let addr_spec = AddrSpec::parse_bytes_with(b"j\xf6rg@example.com", &options).unwrap();
+/// assert_eq!(addr_spec.local_part(), "jörg");
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum Charset {
+    /// [ISO-8859-1](https://en.wikipedia.org/wiki/ISO/IEC_8859-1), i.e. every
+    /// byte is decoded as the code point of the same value.
+    Latin1,
+    /// [Windows-1252](https://encoding.spec.whatwg.org/index-windows-1252.txt)
+    /// as defined by the WHATWG Encoding Standard.
+    Windows1252,
+}
+
+/// The code points of the bytes `0x80..=0x9F` in Windows-1252.
+const WINDOWS_1252: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+impl Charset {
+    /// Decodes a single byte.
+    #[inline]
+    pub fn decode_byte(&self, byte: u8) -> char {
+        match (self, byte) {
+            (Self::Windows1252, 0x80..=0x9F) => WINDOWS_1252[usize::from(byte - 0x80)],
+            _ => char::from(byte),
+        }
+    }
+
+    /// Decodes the bytes into a string.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        bytes.iter().map(|&byte| self.decode_byte(byte)).collect()
+    }
+
+    /// Decodes valid UTF-8 sequences as UTF-8 and only the remaining bytes
+    /// using the charset. Returns the string together with the offsets of the
+    /// bytes in the input for each byte of the string (and its end).
+    fn decode_invalid(&self, bytes: &[u8]) -> (String, Vec<usize>) {
+        let mut decoded = String::with_capacity(bytes.len());
+        let mut offsets = Vec::with_capacity(bytes.len() + 1);
+        let mut offset = 0;
+        for chunk in bytes.utf8_chunks() {
+            decoded.push_str(chunk.valid());
+            offsets.extend(offset..offset + chunk.valid().len());
+            offset += chunk.valid().len();
+            for &byte in chunk.invalid() {
+                let chr = self.decode_byte(byte);
+                decoded.push(chr);
+                offsets.extend(iter::repeat_n(offset, chr.len_utf8()));
+                offset += 1;
+            }
+        }
+        offsets.push(offset);
+        (decoded, offsets)
+    }
+}
+
+/// Parses byte input using the given string parser.
+///
+/// Errors always refer to byte offsets of the original input. Valid UTF-8
+/// sequences are always decoded as UTF-8. The remaining bytes are decoded
+/// using [`ParserOptions::fallback_charset`] if set and reported as
+/// [`ParseErrorKind::InvalidUtf8`] otherwise, unless the grammar fails earlier.
+pub(crate) fn parse_bytes<T>(
+    input: &[u8],
+    options: &ParserOptions,
+    parse: impl Fn(&str, &ParserOptions) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    let error = match str::from_utf8(input) {
+        Ok(input) => return parse(input, options),
+        Err(error) => error,
+    };
+
+    if let Some(charset) = options.fallback_charset {
+        let (decoded, offsets) = charset.decode_invalid(input);
+        return parse(&decoded, options).map_err(|error| error.map_span(|index| offsets[index]));
+    }
+
+    let valid_up_to = error.valid_up_to();
+    let prefix = str::from_utf8(&input[..valid_up_to]).unwrap();
+    match parse(prefix, options) {
+        // Errors at the end of the prefix may be caused by the truncation.
+        Err(error) if error.index() < valid_up_to => Err(error),
+        _ => Err(ParseError::new(
+            ParseErrorKind::InvalidUtf8,
+            "invalid UTF-8 sequence",
+            valid_up_to..valid_up_to + error.error_len().unwrap_or(input.len() - valid_up_to),
+            &[],
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AddrSpec;
+
+    #[test]
+    fn test_parse_bytes() {
+        let addr_spec = AddrSpec::parse_bytes("jörg@example.com".as_bytes()).unwrap();
+        assert_eq!(addr_spec.local_part(), "jörg");
+    }
+
+    #[test]
+    fn test_parse_bytes_with_invalid_utf8() {
+        let error = AddrSpec::parse_bytes(b"j\xf6rg@example.com").unwrap_err();
+        assert_eq!(error.kind(), ParseErrorKind::InvalidUtf8);
+        assert_eq!(error.span(), 1..2);
+
+        let error = AddrSpec::parse_bytes(b"jdoe@example.com\xe2\x82").unwrap_err();
+        assert_eq!(error.kind(), ParseErrorKind::InvalidUtf8);
+        assert_eq!(error.span(), 16..18);
+    }
+
+    #[test]
+    fn test_parse_bytes_with_earlier_error() {
+        let error = AddrSpec::parse_bytes(b"jdoe@@example\xff.com").unwrap_err();
+        assert_eq!(error.kind(), ParseErrorKind::EmptyLabel);
+        assert_eq!(error.span(), 5..5);
+    }
+
+    #[test]
+    fn test_parse_bytes_with_fallback_charset() {
+        let mut options = ParserOptions::new();
+        options.fallback_charset = Some(Charset::Windows1252);
+        let addr_spec = AddrSpec::parse_bytes_with(b"\x80uro@example.com", &options).unwrap();
+        assert_eq!(addr_spec.local_part(), "€uro");
+
+        // Errors refer to the original byte offsets.
+        let error = AddrSpec::parse_bytes_with(b"\xe9\xe9\xe9@.com", &options).unwrap_err();
+        assert_eq!(error.kind(), ParseErrorKind::EmptyLabel);
+        assert_eq!(error.span(), 4..4);
+    }
+
+    #[test]
+    fn test_parse_bytes_with_mixed_input() {
+        let mut options = ParserOptions::new();
+        options.fallback_charset = Some(Charset::Latin1);
+        let addr_spec =
+            AddrSpec::parse_bytes_with(b"j\xc3\xb6rg@ex\xe4mple.com", &options).unwrap();
+        assert_eq!(addr_spec.local_part(), "j\u{f6}rg");
+        assert_eq!(addr_spec.domain(), "ex\u{e4}mple.com");
+
+        // Errors refer to the original byte offsets.
+        let error = AddrSpec::parse_bytes_with(b"\xc3\xb6\xe9@\xe9..com", &options).unwrap_err();
+        assert_eq!(error.kind(), ParseErrorKind::EmptyLabel);
+        assert_eq!(error.span(), 6..6);
+    }
+
+    #[test]
+    fn test_decode_latin1() {
+        assert_eq!(Charset::Latin1.decode(b"caf\xe9 \x80"), "café \u{80}");
+    }
+}
@@ -0,0 +1,174 @@
+use std::{fmt, ops::Range};
+
+/// The severity of a [`Diagnostic`], ordered from least to most severe.
+///
+/// The categories follow the ones used by
+/// [isemail](https://github.com/dominicsayers/isemail).
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum Severity {
+    /// The address is valid per [RFC
+    /// 5321](https://www.rfc-editor.org/rfc/rfc5321) but uses an unusual form,
+    /// e.g. a quoted local part or an address literal.
+    Rfc5321,
+    /// The address is only valid within a message header since it contains
+    /// comments or folding white spaces.
+    Cfws,
+    /// The address uses syntax that is deprecated by [Section 4, RFC
+    /// 5322](https://www.rfc-editor.org/rfc/rfc5322#section-4).
+    Deprecated,
+    /// The address is valid per [RFC
+    /// 5322](https://www.rfc-editor.org/rfc/rfc5322) but cannot be used with
+    /// [RFC 5321](https://www.rfc-editor.org/rfc/rfc5321), i.e. SMTP.
+    Rfc5322,
+}
+
+/// The kind of a [`Diagnostic`].
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum DiagnosticKind {
+    /// The local part is a quoted string.
+    QuotedLocalPart,
+    /// The domain is an address literal, e.g. `[192.0.2.1]`.
+    AddressLiteral,
+    /// A comment is present.
+    Comment,
+    /// A folding white space is present.
+    FoldingWhiteSpace,
+    /// Comments or folding white spaces are present next to the `@`.
+    CfwsNearAt,
+    /// The local part uses the obsolete syntax, e.g. `"john".doe` or
+    /// `john . doe`.
+    ObsoleteLocalPart,
+    /// The domain uses the obsolete syntax, e.g. `example . com`.
+    ObsoleteDomain,
+    /// The domain is a domain literal that is not an address literal.
+    DomainLiteral,
+    /// The local part is longer than 64 octets.
+    LocalPartTooLong,
+    /// The domain is longer than 255 octets.
+    DomainTooLong,
+    /// A label of the domain is longer than 63 octets.
+    LabelTooLong,
+    /// The address is longer than 254 octets.
+    AddressTooLong,
+}
+
+impl DiagnosticKind {
+    /// Returns the severity of the diagnostic kind.
+    pub const fn severity(&self) -> Severity {
+        match self {
+            Self::QuotedLocalPart | Self::AddressLiteral => Severity::Rfc5321,
+            Self::Comment | Self::FoldingWhiteSpace => Severity::Cfws,
+            Self::CfwsNearAt | Self::ObsoleteLocalPart | Self::ObsoleteDomain => {
+                Severity::Deprecated
+            }
+            Self::DomainLiteral
+            | Self::LocalPartTooLong
+            | Self::DomainTooLong
+            | Self::LabelTooLong
+            | Self::AddressTooLong => Severity::Rfc5322,
+        }
+    }
+
+    /// Returns a static message describing the diagnostic kind.
+    pub const fn message(&self) -> &'static str {
+        match self {
+            Self::QuotedLocalPart => "quoted local part used",
+            Self::AddressLiteral => "address literal used",
+            Self::Comment => "comment present",
+            Self::FoldingWhiteSpace => "folding white space present",
+            Self::CfwsNearAt => "comment or folding white space near '@'",
+            Self::ObsoleteLocalPart => "deprecated local part syntax",
+            Self::ObsoleteDomain => "deprecated domain syntax",
+            Self::DomainLiteral => "domain literal is not an address literal",
+            Self::LocalPartTooLong => "local part longer than 64 octets",
+            Self::DomainTooLong => "domain longer than 255 octets",
+            Self::LabelTooLong => "label longer than 63 octets",
+            Self::AddressTooLong => "address longer than 254 octets",
+        }
+    }
+}
+
+/// A non-fatal diagnostic about an address that was parsed successfully.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{AddrSpec, DiagnosticKind, ParserOptions, Severity};
+///
+/// let mut options = ParserOptions::default();
+/// options.white_spaces = true;
+/// options.comments = true;
+/// options.literals = true;
+///
+/// let (addr_spec, diagnostics) =
+///     AddrSpec::parse_with_diagnostics("\"jdoe\"(x)@[192.0.2.1]", &options).unwrap();
+/// assert_eq!(addr_spec.to_string(), "jdoe@[192.0.2.1]");
+/// assert_eq!(
+///     diagnostics.iter().map(|diagnostic| diagnostic.kind()).collect::<Vec<_>>(),
+///     [
+///         DiagnosticKind::QuotedLocalPart,
+///         DiagnosticKind::Comment,
+///         DiagnosticKind::CfwsNearAt,
+///         DiagnosticKind::AddressLiteral,
+///     ]
+/// );
+/// assert_eq!(diagnostics[1].span(), 6..9);
+/// assert_eq!(
+///     diagnostics.iter().map(|diagnostic| diagnostic.severity()).max(),
+///     Some(Severity::Deprecated)
+/// );
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Diagnostic {
+    kind: DiagnosticKind,
+    start: usize,
+    end: usize,
+}
+
+impl Diagnostic {
+    #[inline]
+    pub(crate) const fn new(kind: DiagnosticKind, span: Range<usize>) -> Self {
+        Self {
+            kind,
+            start: span.start,
+            end: span.end,
+        }
+    }
+
+    /// Returns the kind of the diagnostic.
+    #[inline]
+    pub fn kind(&self) -> DiagnosticKind {
+        self.kind
+    }
+
+    /// Returns the severity of the diagnostic.
+    #[inline]
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+
+    /// Returns a static message describing the diagnostic.
+    #[inline]
+    pub fn message(&self) -> &'static str {
+        self.kind.message()
+    }
+
+    /// Returns the byte range of the input the diagnostic refers to.
+    #[inline]
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "warning at index {}: {}",
+            self.start,
+            self.message()
+        )
+    }
+}
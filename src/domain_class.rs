@@ -0,0 +1,155 @@
+use super::unicode;
+
+/// The class of a domain name with respect to the [special-use domain
+/// names](https://www.iana.org/assignments/special-use-domain-names) and
+/// other reserved names.
+///
+/// Domains are compared case-insensitively and a trailing dot is ignored. With
+/// the `idna` feature, Unicode domains are mapped as defined in [UTS
+/// #46](https://www.unicode.org/reports/tr46/) first, so that e.g. full-width
+/// forms are recognized as well. Without it, only the Unicode full stops are
+/// mapped and the domain is lowercased.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::DomainClass;
+///
+/// assert_eq!(DomainClass::of("LocalHost."), DomainClass::Localhost);
+/// assert_eq!(DomainClass::of("printer.local"), DomainClass::Local);
+/// assert_eq!(DomainClass::of("mail.example.com"), DomainClass::Example);
+/// assert_eq!(DomainClass::of("router.home.arpa"), DomainClass::HomeArpa);
+/// assert!(DomainClass::of("example.de").is_global());
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum DomainClass {
+    /// `localhost` and its subdomains, which resolve to a loopback address
+    /// ([RFC 6761](https://www.rfc-editor.org/rfc/rfc6761#section-6.3)).
+    Localhost,
+    /// Multicast DNS names under `.local`
+    /// ([RFC 6762](https://www.rfc-editor.org/rfc/rfc6762)).
+    Local,
+    /// Names under `.test`, reserved for testing
+    /// ([RFC 6761](https://www.rfc-editor.org/rfc/rfc6761#section-6.2)).
+    Test,
+    /// Names under `.example` and the second-level domains `example.com`,
+    /// `example.net` and `example.org`, reserved for documentation
+    /// ([RFC 6761](https://www.rfc-editor.org/rfc/rfc6761#section-6.5)).
+    Example,
+    /// Names under `.invalid`, which never resolve
+    /// ([RFC 6761](https://www.rfc-editor.org/rfc/rfc6761#section-6.4)).
+    Invalid,
+    /// Tor onion services under `.onion`
+    /// ([RFC 7686](https://www.rfc-editor.org/rfc/rfc7686)).
+    Onion,
+    /// Names under `.internal`, reserved for private use by
+    /// [ICANN](https://www.icann.org/en/board-activities-and-meetings/materials/approved-resolutions-special-meeting-of-the-icann-board-29-07-2024-en#section2.a).
+    Internal,
+    /// Names under `.alt`, reserved for non-DNS resolution contexts
+    /// ([RFC 9476](https://www.rfc-editor.org/rfc/rfc9476)).
+    Alt,
+    /// Residential networks under `home.arpa`
+    /// ([RFC 8375](https://www.rfc-editor.org/rfc/rfc8375)).
+    HomeArpa,
+    /// Other names under `.arpa`, which is reserved for infrastructure such as
+    /// reverse lookups
+    /// ([RFC 3172](https://www.rfc-editor.org/rfc/rfc3172)).
+    Arpa,
+    /// Any other domain.
+    Global,
+}
+
+impl DomainClass {
+    /// Returns the class of the domain.
+    pub fn of(domain: &str) -> Self {
+        let domain = unicode::fold_domain(domain);
+        let domain = domain.strip_suffix('.').unwrap_or(&domain);
+        let mut labels = domain.rsplit('.');
+        let tld = labels.next().unwrap_or_default();
+        let sld = labels.next();
+        match (tld, sld) {
+            ("localhost", _) => Self::Localhost,
+            ("local", _) => Self::Local,
+            ("test", _) => Self::Test,
+            ("example", _) | ("com" | "net" | "org", Some("example")) => Self::Example,
+            ("invalid", _) => Self::Invalid,
+            ("onion", _) => Self::Onion,
+            ("internal", _) => Self::Internal,
+            ("alt", _) => Self::Alt,
+            ("arpa", Some("home")) => Self::HomeArpa,
+            ("arpa", _) => Self::Arpa,
+            _ => Self::Global,
+        }
+    }
+
+    /// Returns whether the domain is not a special-use or reserved name.
+    #[inline]
+    pub fn is_global(self) -> bool {
+        self == Self::Global
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_special_use_domains() {
+        for (domain, class) in [
+            ("localhost", DomainClass::Localhost),
+            ("app.localhost", DomainClass::Localhost),
+            ("printer.local", DomainClass::Local),
+            ("test", DomainClass::Test),
+            ("foo.test", DomainClass::Test),
+            ("foo.example", DomainClass::Example),
+            ("example.com", DomainClass::Example),
+            ("www.example.net", DomainClass::Example),
+            ("example.org", DomainClass::Example),
+            ("foo.invalid", DomainClass::Invalid),
+            (
+                "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion",
+                DomainClass::Onion,
+            ),
+            ("db.corp.internal", DomainClass::Internal),
+            ("foo.alt", DomainClass::Alt),
+            ("router.home.arpa", DomainClass::HomeArpa),
+            ("1.2.0.192.in-addr.arpa", DomainClass::Arpa),
+            ("example.de", DomainClass::Global),
+            ("com", DomainClass::Global),
+            ("notexample.com", DomainClass::Global),
+            ("localhost.com", DomainClass::Global),
+        ] {
+            assert_eq!(DomainClass::of(domain), class, "{domain:?}");
+        }
+    }
+
+    #[test]
+    fn test_case_and_trailing_dot() {
+        assert_eq!(DomainClass::of("LOCALHOST"), DomainClass::Localhost);
+        assert_eq!(DomainClass::of("localhost."), DomainClass::Localhost);
+        assert_eq!(DomainClass::of("Foo.Example.COM."), DomainClass::Example);
+        assert_eq!(DomainClass::of(""), DomainClass::Global);
+        assert_eq!(DomainClass::of("."), DomainClass::Global);
+    }
+
+    #[test]
+    fn test_unicode_domains() {
+        assert_eq!(DomainClass::of("m\u{fc}nchen.test"), DomainClass::Test);
+        assert_eq!(DomainClass::of("xn--mnchen-3ya.test"), DomainClass::Test);
+        assert_eq!(DomainClass::of("M\u{dc}NCHEN.TEST"), DomainClass::Test);
+        assert_eq!(DomainClass::of("foo\u{3002}local"), DomainClass::Local);
+        assert_eq!(DomainClass::of("m\u{fc}nchen.de"), DomainClass::Global);
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn test_full_width_domains() {
+        assert_eq!(
+            DomainClass::of(
+                "\u{ff4c}\u{ff4f}\u{ff43}\u{ff41}\u{ff4c}\u{ff48}\u{ff4f}\u{ff53}\u{ff54}"
+            ),
+            DomainClass::Localhost
+        );
+    }
+}
@@ -0,0 +1,594 @@
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use super::{parser::is_not_dtext, ParseError, ParseErrorKind, Token};
+
+/// Typed content of a domain literal, i.e. the text between the brackets of
+/// e.g. `jdoe@[192.0.2.1]`.
+///
+/// Address literals are defined in [Section 4.1.3, RFC
+/// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.3). Contents that
+/// look like IP addresses must be valid IP addresses and are serialized
+/// canonically, i.e. without leading zeros and with compressed IPv6
+/// addresses.
+///
+/// # Examples
+///
+/// ```
+/// use std::net::Ipv6Addr;
+///
+/// use addr_spec::DomainLiteral;
+///
+/// let literal: DomainLiteral = "IPv6:2001:0db8:0:0:0:0:0:1".parse().unwrap();
+/// assert_eq!(literal, DomainLiteral::Ipv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)));
+/// assert_eq!(literal.to_string(), "IPv6:2001:db8::1");
+///
+/// assert!("IPv6:::::".parse::<DomainLiteral>().is_err());
+/// assert!("192.0.2.256".parse::<DomainLiteral>().is_err());
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum DomainLiteral {
+    /// An IPv4 address literal, e.g. `192.0.2.1`.
+    Ipv4(Ipv4Addr),
+    /// An IPv6 address literal, e.g. `IPv6:2001:db8::1`.
+    Ipv6(Ipv6Addr),
+    /// A general address literal, e.g. `x-tag:content`.
+    General {
+        /// The standardized tag, i.e. an LDH string.
+        tag: String,
+        /// The content after the tag.
+        content: String,
+    },
+    /// A domain literal that is not an address literal, e.g.
+    /// `machine.example`, as allowed by [RFC
+    /// 5322](https://www.rfc-editor.org/rfc/rfc5322#section-3.4.1).
+    Other(String),
+}
+
+impl DomainLiteral {
+    /// Returns whether the literal is an address literal as defined in [RFC
+    /// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.3).
+    #[inline]
+    pub fn is_address_literal(&self) -> bool {
+        !matches!(self, Self::Other(_))
+    }
+
+    /// Returns the IP address of the literal, if any.
+    #[inline]
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self {
+            Self::Ipv4(address) => Some(IpAddr::V4(*address)),
+            Self::Ipv6(address) => Some(IpAddr::V6(*address)),
+            _ => None,
+        }
+    }
+
+    /// Returns the class of the IP address of the literal, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{DomainLiteral, IpClass};
+    ///
+    /// let literal: DomainLiteral = "127.0.0.1".parse().unwrap();
+    /// assert_eq!(literal.ip_class(), Some(IpClass::Loopback));
+    /// ```
+    #[inline]
+    pub fn ip_class(&self) -> Option<IpClass> {
+        self.ip().map(IpClass::of)
+    }
+
+    /// Classifies the content of a domain literal, returning `None` if it
+    /// looks like but is not a valid IP address literal.
+    pub(crate) fn classify(literal: &str) -> Option<Self> {
+        if !literal.is_empty()
+            && literal
+                .bytes()
+                .all(|byte| byte.is_ascii_digit() || byte == b'.')
+        {
+            return parse_ipv4(literal).map(Self::Ipv4);
+        }
+        if let Some((tag, content)) = literal.split_once(':') {
+            if tag.eq_ignore_ascii_case("IPv6") {
+                return is_ipv6_comp(content)
+                    .then(|| Ipv6Addr::from_str(content).ok())
+                    .flatten()
+                    .map(Self::Ipv6);
+            }
+            if is_ldh_str(tag) && !content.is_empty() && content.chars().all(is_dcontent) {
+                return Some(Self::General {
+                    tag: tag.to_owned(),
+                    content: content.to_owned(),
+                });
+            }
+        }
+        Some(Self::Other(literal.to_owned()))
+    }
+
+    /// Returns whether the content of a domain literal that is cut off at the
+    /// end of the input can still become valid by appending more content. If
+    /// `address_literal` is set, it must become an address literal.
+    pub(crate) fn is_viable_prefix(literal: &str, address_literal: bool) -> bool {
+        match literal.split_once(':') {
+            Some((tag, content)) if tag.eq_ignore_ascii_case("IPv6") => is_ipv6_prefix(content),
+            Some((tag, content)) => {
+                !address_literal || (is_ldh_str(tag) && content.chars().all(is_dcontent))
+            }
+            // The literal may still become a tag or an IPv4 address.
+            None => {
+                !address_literal
+                    || literal
+                        .bytes()
+                        .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+                    || is_ipv4_prefix(literal)
+            }
+        }
+    }
+}
+
+/// The class of an IP address, based on the [IANA special-purpose address
+/// registries](https://www.iana.org/assignments/iana-ipv4-special-registry).
+///
+/// This allows rejecting or flagging domain literals that do not point at a
+/// globally reachable host, e.g. `jdoe@[127.0.0.1]`.
+///
+/// # Examples
+///
+/// ```
+/// use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+///
+/// use addr_spec::IpClass;
+///
+/// assert_eq!(IpClass::of(Ipv4Addr::new(10, 0, 0, 1).into()), IpClass::Private);
+/// assert_eq!(
+///     IpClass::of("::ffff:169.254.0.1".parse::<IpAddr>().unwrap()),
+///     IpClass::LinkLocal
+/// );
+/// assert!(IpClass::of(Ipv6Addr::new(0x2606, 0x4700, 0, 0, 0, 0, 0, 1).into()).is_global());
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum IpClass {
+    /// The unspecified address, i.e. `0.0.0.0` or `::`.
+    Unspecified,
+    /// A loopback address, i.e. `127.0.0.0/8` or `::1`.
+    Loopback,
+    /// A private address as defined in [RFC
+    /// 1918](https://www.rfc-editor.org/rfc/rfc1918), an IPv6 unique local
+    /// address (`fc00::/7`) or a local-use NAT64 address (`64:ff9b:1::/48`,
+    /// [RFC 8215](https://www.rfc-editor.org/rfc/rfc8215)).
+    Private,
+    /// An address of the shared address space `100.64.0.0/10` used for
+    /// carrier-grade NAT.
+    SharedAddressSpace,
+    /// A link-local address, i.e. `169.254.0.0/16` or `fe80::/10`.
+    LinkLocal,
+    /// A multicast address, i.e. `224.0.0.0/4` or `ff00::/8`.
+    Multicast,
+    /// The limited broadcast address `255.255.255.255`.
+    Broadcast,
+    /// An address reserved for documentation, e.g. `192.0.2.0/24` or
+    /// `2001:db8::/32`.
+    Documentation,
+    /// An address reserved for benchmarking, i.e. `198.18.0.0/15` or
+    /// `2001:2::/48`.
+    Benchmarking,
+    /// Any other special-purpose or reserved address, e.g. `240.0.0.0/4`.
+    Reserved,
+    /// A globally reachable address.
+    Global,
+}
+
+impl IpClass {
+    /// Returns the class of the IP address.
+    ///
+    /// IPv6 addresses embedding an IPv4 address, i.e. IPv4-mapped, NAT64 and
+    /// 6to4 addresses, are classified by the embedded IPv4 address.
+    pub fn of(address: IpAddr) -> Self {
+        match address {
+            IpAddr::V4(address) => Self::of_ipv4(address),
+            IpAddr::V6(address) => Self::of_ipv6(address),
+        }
+    }
+
+    fn of_ipv4(address: Ipv4Addr) -> Self {
+        match address.octets() {
+            [0, 0, 0, 0] => Self::Unspecified,
+            [255, 255, 255, 255] => Self::Broadcast,
+            [127, ..] => Self::Loopback,
+            [10, ..] | [172, 16..=31, ..] | [192, 168, ..] => Self::Private,
+            [100, 64..=127, ..] => Self::SharedAddressSpace,
+            [169, 254, ..] => Self::LinkLocal,
+            [192, 0, 2, _] | [198, 51, 100, _] | [203, 0, 113, _] => Self::Documentation,
+            [198, 18..=19, ..] => Self::Benchmarking,
+            [224..=239, ..] => Self::Multicast,
+            // The PCP and TURN anycast addresses are globally reachable.
+            [192, 0, 0, 9 | 10] => Self::Global,
+            [0, ..] | [192, 0, 0, _] | [240..=255, ..] => Self::Reserved,
+            _ => Self::Global,
+        }
+    }
+
+    fn of_ipv6(address: Ipv6Addr) -> Self {
+        let segments = address.segments();
+        match segments {
+            [0, 0, 0, 0, 0, 0, 0, 0] => Self::Unspecified,
+            [0, 0, 0, 0, 0, 0, 0, 1] => Self::Loopback,
+            // IPv4-mapped and NAT64 addresses.
+            [0, 0, 0, 0, 0, 0xffff, ..] | [0x64, 0xff9b, 0, 0, 0, 0, ..] => {
+                let [.., a, b, c, d] = address.octets();
+                Self::of_ipv4(Ipv4Addr::new(a, b, c, d))
+            }
+            // 6to4 addresses.
+            [0x2002, high, low, ..] => {
+                let [a, b] = high.to_be_bytes();
+                let [c, d] = low.to_be_bytes();
+                Self::of_ipv4(Ipv4Addr::new(a, b, c, d))
+            }
+            [0x2001, 0xdb8, ..] | [0x3fff, 0..=0x0fff, ..] => Self::Documentation,
+            [0x2001, 0x2, 0, ..] => Self::Benchmarking,
+            // Unique local and local-use NAT64 addresses.
+            [0xfc00..=0xfdff, ..] | [0x64, 0xff9b, 0x1, ..] => Self::Private,
+            [0xfe80..=0xfebf, ..] => Self::LinkLocal,
+            [0xff00..=0xffff, ..] => Self::Multicast,
+            // The PCP and TURN anycast addresses as well as the AMT, AS112-v6
+            // and ORCHIDv2 blocks are globally reachable.
+            [0x2001, 0x1, 0, 0, 0, 0, 0, 1 | 2]
+            | [0x2001, 0x3, ..]
+            | [0x2001, 0x4, 0x112, ..]
+            | [0x2001, 0x20..=0x2f, ..] => Self::Global,
+            [0, ..] | [0x100, 0, 0, 0, ..] | [0x2001, 0..=0x1ff, ..] | [0xfec0..=0xfeff, ..] => {
+                Self::Reserved
+            }
+            _ => Self::Global,
+        }
+    }
+
+    /// Returns whether addresses of the class are globally reachable.
+    #[inline]
+    pub fn is_global(&self) -> bool {
+        *self == Self::Global
+    }
+}
+
+/// Parses an IPv4 address whose parts may have leading zeros as allowed by
+/// `Snum` in [RFC 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.3).
+fn parse_ipv4(literal: &str) -> Option<Ipv4Addr> {
+    let mut octets = [0u8; 4];
+    let mut parts = literal.split('.');
+    for octet in &mut octets {
+        let part = parts.next()?;
+        if part.is_empty() || part.len() > 3 {
+            return None;
+        }
+        *octet = part.parse().ok()?;
+    }
+    parts.next().is_none().then(|| Ipv4Addr::from(octets))
+}
+
+/// Returns whether a compressed IPv6 address, if it is one, satisfies the
+/// `IPv6-comp` and `IPv6v4-comp` forms of [RFC
+/// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.3), i.e. `::`
+/// stands for at least two groups of zeros.
+fn is_ipv6_comp(literal: &str) -> bool {
+    let Some((head, tail)) = literal.split_once("::") else {
+        return true;
+    };
+    let groups = [head, tail]
+        .into_iter()
+        .flat_map(|part| part.split(':'))
+        .filter(|group| !group.is_empty())
+        // An IPv4 address takes up two groups.
+        .map(|group| if group.contains('.') { 2 } else { 1 })
+        .sum::<usize>();
+    groups <= 6
+}
+
+/// Returns whether the value can still become an IPv4 address by appending
+/// more digits and periods.
+fn is_ipv4_prefix(value: &str) -> bool {
+    let is_snum = |part: &str| {
+        part.len() <= 3
+            && part.bytes().all(|byte| byte.is_ascii_digit())
+            && part.parse::<u8>().is_ok()
+    };
+    let parts = value.split('.').collect::<Vec<_>>();
+    let Some((last, init)) = parts.split_last() else {
+        return false;
+    };
+    parts.len() <= 4 && init.iter().all(|part| is_snum(part)) && (last.is_empty() || is_snum(last))
+}
+
+/// Returns whether the value can still become an address satisfying the
+/// `IPv6-addr` form of [RFC
+/// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.3) by appending
+/// more content.
+fn is_ipv6_prefix(value: &str) -> bool {
+    if !value
+        .bytes()
+        .all(|byte| byte.is_ascii_hexdigit() || byte == b':' || byte == b'.')
+        || value.contains(":::")
+        || value.matches("::").count() > 1
+        || (value.starts_with(':') && !value.starts_with("::") && value.len() > 1)
+    {
+        return false;
+    }
+    let compressed = value.contains("::");
+    let (init, last) = value.rsplit_once(':').unwrap_or(("", value));
+    if init
+        .split(':')
+        .any(|group| group.len() > 4 || group.contains('.'))
+    {
+        return false;
+    }
+    let groups = init.split(':').filter(|group| !group.is_empty()).count();
+    // An IPv4 address takes up two groups and must come last.
+    if last.contains('.') {
+        return is_ipv4_prefix(last)
+            && if compressed {
+                groups + 2 <= 6
+            } else {
+                groups + 2 == 8
+            };
+    }
+    if last.len() > 4 {
+        return false;
+    }
+    // A group must follow a single trailing ':'.
+    let pending = !last.is_empty() || (value.ends_with(':') && !value.ends_with("::"));
+    let groups = groups + usize::from(pending);
+    if compressed {
+        groups <= 6
+    } else {
+        groups <= 8
+    }
+}
+
+/// Returns whether the character is valid in the content of a general address
+/// literal.
+const fn is_dcontent(chr: char) -> bool {
+    matches!(chr, '!'..='Z' | '^'..='~')
+}
+
+/// Returns whether the value is a `Standardized-tag` of [RFC
+/// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.3), i.e. an
+/// `Ldh-str`, which may start but not end with a hyphen.
+fn is_ldh_str(value: &str) -> bool {
+    !value.is_empty()
+        && !value.ends_with('-')
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+}
+
+impl FromStr for DomainLiteral {
+    type Err = ParseError;
+
+    /// Parses the content of a domain literal without its brackets.
+    fn from_str(literal: &str) -> Result<Self, Self::Err> {
+        if let Some((index, chr)) = literal.char_indices().find(|&(_, chr)| is_not_dtext(chr)) {
+            return Err(ParseError::invalid_character(
+                chr,
+                "invalid character in literal domain",
+                index,
+                &[Token::Dtext],
+            ));
+        }
+        Self::classify(literal).ok_or(ParseError::new(
+            ParseErrorKind::InvalidAddressLiteral,
+            "invalid address literal",
+            0..literal.len(),
+            &[Token::AddressLiteral],
+        ))
+    }
+}
+
+impl fmt::Display for DomainLiteral {
+    /// Writes the canonical content of the domain literal without its
+    /// brackets.
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ipv4(address) => write!(formatter, "{address}"),
+            Self::Ipv6(address) => write!(formatter, "IPv6:{address}"),
+            Self::General { tag, content } => write!(formatter, "{tag}:{content}"),
+            Self::Other(literal) => formatter.write_str(literal),
+        }
+    }
+}
+
+impl From<Ipv4Addr> for DomainLiteral {
+    #[inline]
+    fn from(address: Ipv4Addr) -> Self {
+        Self::Ipv4(address)
+    }
+}
+
+impl From<Ipv6Addr> for DomainLiteral {
+    #[inline]
+    fn from(address: Ipv6Addr) -> Self {
+        Self::Ipv6(address)
+    }
+}
+
+impl From<IpAddr> for DomainLiteral {
+    #[inline]
+    fn from(address: IpAddr) -> Self {
+        match address {
+            IpAddr::V4(address) => Self::Ipv4(address),
+            IpAddr::V6(address) => Self::Ipv6(address),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ipv4() {
+        assert_eq!(
+            "192.0.2.1".parse::<DomainLiteral>().unwrap(),
+            DomainLiteral::Ipv4(Ipv4Addr::new(192, 0, 2, 1))
+        );
+        let literal = "192.000.002.001".parse::<DomainLiteral>().unwrap();
+        assert_eq!(literal.to_string(), "192.0.2.1");
+        for literal in [
+            "192.0.2",
+            "192.0.2.1.1",
+            "192.0..1",
+            "192.0.2.256",
+            "1.0.0.0001",
+        ] {
+            assert_eq!(
+                literal.parse::<DomainLiteral>().unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::InvalidAddressLiteral,
+                    "invalid address literal",
+                    0..literal.len(),
+                    &[Token::AddressLiteral]
+                ),
+                "{literal:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_ipv6() {
+        let literal = "ipv6:2001:DB8:0000::0:1".parse::<DomainLiteral>().unwrap();
+        assert_eq!(literal.to_string(), "IPv6:2001:db8::1");
+        assert_eq!(
+            "IPv6:::ffff:192.0.2.1"
+                .parse::<DomainLiteral>()
+                .unwrap()
+                .to_string(),
+            "IPv6:::ffff:192.0.2.1"
+        );
+        for literal in [
+            "IPv6:1:2:3:4:5::6",
+            "IPv6:1:2:3::4:192.0.2.1",
+            "IPv6:1:2:3:4:5:6:7:8",
+        ] {
+            assert!(literal.parse::<DomainLiteral>().is_ok(), "{literal:?}");
+        }
+        for literal in [
+            "IPv6:::::",
+            "IPv6:",
+            "IPv6:2001:db8::1::1",
+            "IPv6:192.0.2.1",
+            "IPv6:1:2:3:4:5:6::7",
+            "IPv6:1::2:3:4:5:6:7",
+            "IPv6:1:2:3:4::5:192.0.2.1",
+        ] {
+            assert!(literal.parse::<DomainLiteral>().is_err(), "{literal:?}");
+        }
+    }
+
+    #[test]
+    fn test_ipv4_classes() {
+        for (address, class) in [
+            ("0.0.0.0", IpClass::Unspecified),
+            ("0.1.2.3", IpClass::Reserved),
+            ("127.0.0.1", IpClass::Loopback),
+            ("10.1.2.3", IpClass::Private),
+            ("172.16.0.1", IpClass::Private),
+            ("172.32.0.1", IpClass::Global),
+            ("192.168.1.1", IpClass::Private),
+            ("100.64.0.1", IpClass::SharedAddressSpace),
+            ("169.254.169.254", IpClass::LinkLocal),
+            ("192.0.0.8", IpClass::Reserved),
+            ("192.0.0.9", IpClass::Global),
+            ("192.0.0.10", IpClass::Global),
+            ("192.0.0.11", IpClass::Reserved),
+            ("192.0.2.1", IpClass::Documentation),
+            ("198.51.100.1", IpClass::Documentation),
+            ("203.0.113.1", IpClass::Documentation),
+            ("198.19.0.1", IpClass::Benchmarking),
+            ("224.0.0.1", IpClass::Multicast),
+            ("240.0.0.1", IpClass::Reserved),
+            ("255.255.255.255", IpClass::Broadcast),
+            ("8.8.8.8", IpClass::Global),
+        ] {
+            assert_eq!(IpClass::of(address.parse().unwrap()), class, "{address:?}");
+        }
+    }
+
+    #[test]
+    fn test_ipv6_classes() {
+        for (address, class) in [
+            ("::", IpClass::Unspecified),
+            ("::1", IpClass::Loopback),
+            ("::ffff:127.0.0.1", IpClass::Loopback),
+            ("64:ff9b::10.0.0.1", IpClass::Private),
+            ("64:ff9b:1::7f00:1", IpClass::Private),
+            ("64:ff9b:1:ffff::1", IpClass::Private),
+            ("2002:c0a8:101::1", IpClass::Private),
+            ("2001:db8::1", IpClass::Documentation),
+            ("3fff::1", IpClass::Documentation),
+            ("2001:2::1", IpClass::Benchmarking),
+            ("fd00::1", IpClass::Private),
+            ("fe80::1", IpClass::LinkLocal),
+            ("ff02::1", IpClass::Multicast),
+            ("100::1", IpClass::Reserved),
+            ("2001::1", IpClass::Reserved),
+            ("2001:1::1", IpClass::Global),
+            ("2001:1::2", IpClass::Global),
+            ("2001:1::3", IpClass::Reserved),
+            ("2001:3::1", IpClass::Global),
+            ("2001:4:112::1", IpClass::Global),
+            ("2001:4:113::1", IpClass::Reserved),
+            ("2001:20::1", IpClass::Global),
+            ("2001:2f::1", IpClass::Global),
+            ("2001:30::1", IpClass::Reserved),
+            ("::127.0.0.1", IpClass::Reserved),
+            ("2606:4700::1111", IpClass::Global),
+        ] {
+            assert_eq!(IpClass::of(address.parse().unwrap()), class, "{address:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_general() {
+        assert_eq!(
+            "x-tag:some!content".parse::<DomainLiteral>().unwrap(),
+            DomainLiteral::General {
+                tag: "x-tag".into(),
+                content: "some!content".into()
+            }
+        );
+        assert_eq!(
+            "-tag:content".parse::<DomainLiteral>().unwrap(),
+            DomainLiteral::General {
+                tag: "-tag".into(),
+                content: "content".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_other() {
+        let literal = "machine.example".parse::<DomainLiteral>().unwrap();
+        assert_eq!(literal, DomainLiteral::Other("machine.example".into()));
+        assert!(!literal.is_address_literal());
+        assert_eq!(
+            "tag-:content".parse::<DomainLiteral>().unwrap(),
+            DomainLiteral::Other("tag-:content".into())
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_character() {
+        assert_eq!(
+            "192.0.2.1]".parse::<DomainLiteral>().unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: ']' },
+                "invalid character in literal domain",
+                9..10,
+                &[Token::Dtext]
+            )
+        );
+    }
+}
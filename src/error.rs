@@ -0,0 +1,329 @@
+use std::{error::Error, fmt, ops::Range};
+
+use super::IpClass;
+
+/// The kind of a [`ParseError`].
+///
+/// Unlike the error message, the kind is stable and can be used as a
+/// machine-readable error code (see [`ParseErrorKind::code`]).
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// A label of a dot-atom (i.e. the text between two periods) or an atom
+    /// is empty.
+    EmptyLabel,
+    /// A quoted string is missing its closing `"`.
+    UnterminatedQuote,
+    /// A comment is missing its closing `)`.
+    UnterminatedComment,
+    /// A domain literal is missing its closing `]`.
+    UnterminatedDomainLiteral,
+    /// An angle address or path is missing its closing `>`.
+    UnterminatedAngleAddress,
+    /// A character is not allowed at this position.
+    InvalidCharacter {
+        /// The invalid character.
+        found: char,
+    },
+    /// The input ended before the `@` between the local part and the domain.
+    MissingAt,
+    /// The input continues after a complete value.
+    TrailingInput,
+    /// The input ended unexpectedly.
+    UnexpectedEnd,
+    /// A domain literal is not an address literal as defined in [Section
+    /// 4.1.3, RFC 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.3).
+    InvalidAddressLiteral,
+    /// A non-ASCII character was used without the `SMTPUTF8` extension.
+    SmtpUtf8Required,
+    /// The byte input is not valid UTF-8.
+    InvalidUtf8,
+    /// The local part is longer than 64 octets.
+    LocalPartTooLong,
+    /// The domain is longer than 255 octets.
+    DomainTooLong,
+    /// A label of the domain is longer than 63 octets.
+    LabelTooLong,
+    /// The address is longer than 254 octets, i.e. its path including the
+    /// angle brackets is longer than 256 octets.
+    AddressTooLong,
+    /// The top-level domain of a hostname is all-numeric.
+    NumericTopLevelDomain,
+    /// The domain consists of a single label although multiple labels are
+    /// required.
+    SingleLabelDomain,
+    /// The domain literal is an IP address that is not globally reachable.
+    RestrictedAddressLiteral {
+        /// The class of the IP address.
+        class: IpClass,
+    },
+    /// An A-label (`xn--…`) is not valid Punycode or does not decode into a
+    /// valid U-label.
+    InvalidALabel,
+    /// The code point is not allowed in internationalized domain names as
+    /// defined in [RFC 5892](https://www.rfc-editor.org/rfc/rfc5892), or in
+    /// local parts by the PRECIS profile as defined in [RFC
+    /// 8264](https://www.rfc-editor.org/rfc/rfc8264).
+    DisallowedCodePoint {
+        /// The disallowed code point.
+        found: char,
+    },
+    /// The contextual rule (CONTEXTJ or CONTEXTO) of the code point as defined
+    /// in [RFC 5892](https://www.rfc-editor.org/rfc/rfc5892#appendix-A) is not
+    /// satisfied.
+    ContextRule {
+        /// The code point whose rule is not satisfied.
+        found: char,
+    },
+    /// The label, or the local part with a PRECIS profile, does not satisfy the
+    /// Bidi rule of [RFC 5893](https://www.rfc-editor.org/rfc/rfc5893#section-2).
+    BidiRule,
+    /// The local part or a label of the domain does not satisfy the required
+    /// restriction level.
+    RestrictionLevelNotMet,
+}
+
+impl ParseErrorKind {
+    /// Returns a stable, machine-readable code for the error kind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, ParseErrorKind};
+    ///
+    /// let error = "jdoe".parse::<AddrSpec>().unwrap_err();
+    /// assert_eq!(error.kind(), ParseErrorKind::MissingAt);
+    /// assert_eq!(error.kind().code(), "missing_at");
+    /// ```
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::EmptyLabel => "empty_label",
+            Self::UnterminatedQuote => "unterminated_quote",
+            Self::UnterminatedComment => "unterminated_comment",
+            Self::UnterminatedDomainLiteral => "unterminated_domain_literal",
+            Self::UnterminatedAngleAddress => "unterminated_angle_address",
+            Self::InvalidCharacter { .. } => "invalid_character",
+            Self::MissingAt => "missing_at",
+            Self::TrailingInput => "trailing_input",
+            Self::UnexpectedEnd => "unexpected_end",
+            Self::InvalidAddressLiteral => "invalid_address_literal",
+            Self::SmtpUtf8Required => "smtputf8_required",
+            Self::InvalidUtf8 => "invalid_utf8",
+            Self::LocalPartTooLong => "local_part_too_long",
+            Self::DomainTooLong => "domain_too_long",
+            Self::LabelTooLong => "label_too_long",
+            Self::AddressTooLong => "address_too_long",
+            Self::NumericTopLevelDomain => "numeric_top_level_domain",
+            Self::SingleLabelDomain => "single_label_domain",
+            Self::RestrictedAddressLiteral { .. } => "restricted_address_literal",
+            Self::InvalidALabel => "invalid_a_label",
+            Self::DisallowedCodePoint { .. } => "disallowed_code_point",
+            Self::ContextRule { .. } => "context_rule",
+            Self::BidiRule => "bidi_rule",
+            Self::RestrictionLevelNotMet => "restriction_level_not_met",
+        }
+    }
+}
+
+/// A token the parser expected when an error occurred.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum Token {
+    /// A specific character.
+    Char(char),
+    /// A specific (case-insensitive) keyword, e.g. `MAIL FROM:`.
+    Keyword(&'static str),
+    /// A character allowed in atoms (`atext`).
+    Atext,
+    /// A character allowed in quoted strings (`qtext`).
+    Qtext,
+    /// A character allowed in comments (`ctext`).
+    Ctext,
+    /// A character allowed in domain literals (`dtext`).
+    Dtext,
+    /// A visible character or white space, e.g. after a backslash.
+    Vchar,
+    /// An ASCII letter or digit.
+    LetterOrDigit,
+    /// A character allowed in the local part of a [valid email
+    /// address](https://html.spec.whatwg.org/multipage/input.html#valid-e-mail-address)
+    /// in HTML.
+    Html5LocalPart,
+    /// An address literal, e.g. `192.0.2.1` or `IPv6:2001:db8::1`.
+    AddressLiteral,
+    /// An ESMTP parameter keyword.
+    EsmtpKeyword,
+    /// An ESMTP parameter value.
+    EsmtpValue,
+    /// The end of the input.
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Char(chr) => write!(formatter, "'{chr}'"),
+            Self::Keyword(keyword) => write!(formatter, "'{keyword}'"),
+            Self::Atext => formatter.write_str("atom character"),
+            Self::Qtext => formatter.write_str("quoted string character"),
+            Self::Ctext => formatter.write_str("comment character"),
+            Self::Dtext => formatter.write_str("domain literal character"),
+            Self::Vchar => formatter.write_str("visible character"),
+            Self::LetterOrDigit => formatter.write_str("letter or digit"),
+            Self::Html5LocalPart => formatter.write_str("local part character"),
+            Self::AddressLiteral => formatter.write_str("address literal"),
+            Self::EsmtpKeyword => formatter.write_str("ESMTP keyword"),
+            Self::EsmtpValue => formatter.write_str("ESMTP value"),
+            Self::End => formatter.write_str("end of input"),
+        }
+    }
+}
+
+/// A error that can occur when parsing or creating an address specification.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{AddrSpec, ParseErrorKind, Token};
+///
+/// let error = "jd\u{7}oe@example.com".parse::<AddrSpec>().unwrap_err();
+/// assert_eq!(error.kind(), ParseErrorKind::InvalidCharacter { found: '\u{7}' });
+/// assert_eq!(error.span(), 2..3);
+/// assert_eq!(error.expected(), [Token::Atext, Token::Char('.'), Token::Char('@')]);
+/// assert_eq!(error.to_string(), "parse error at index 2: expected '@'");
+///
+/// let error = "jdoe".parse::<AddrSpec>().unwrap_err();
+/// assert_eq!(error.kind(), ParseErrorKind::MissingAt);
+/// assert_eq!(error.span(), 4..4);
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    message: &'static str,
+    start: usize,
+    end: usize,
+    expected: &'static [Token],
+}
+
+impl ParseError {
+    #[inline]
+    pub(crate) const fn new(
+        kind: ParseErrorKind,
+        message: &'static str,
+        span: Range<usize>,
+        expected: &'static [Token],
+    ) -> Self {
+        Self {
+            kind,
+            message,
+            start: span.start,
+            end: span.end,
+            expected,
+        }
+    }
+
+    /// Moves the span of the error by the given offset.
+    #[cfg(any(feature = "idna", feature = "precis"))]
+    #[inline]
+    pub(crate) const fn offset(mut self, offset: usize) -> Self {
+        self.start += offset;
+        self.end += offset;
+        self
+    }
+
+    /// Creates an error for an invalid character at the given index.
+    #[inline]
+    pub(crate) fn invalid_character(
+        chr: char,
+        message: &'static str,
+        index: usize,
+        expected: &'static [Token],
+    ) -> Self {
+        Self::new(
+            ParseErrorKind::InvalidCharacter { found: chr },
+            message,
+            index..index + chr.len_utf8(),
+            expected,
+        )
+    }
+
+    /// Maps the span of the error, e.g. to offsets of the undecoded input.
+    #[inline]
+    pub(crate) fn map_span(mut self, map: impl Fn(usize) -> usize) -> Self {
+        self.start = map(self.start);
+        self.end = map(self.end);
+        self
+    }
+
+    /// Returns the kind of the error.
+    #[inline]
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Returns a static error message.
+    #[inline]
+    pub fn message(&self) -> &'static str {
+        self.message
+    }
+
+    /// Returns the byte index where the error occurred.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the byte range of the input the error refers to.
+    ///
+    /// The range is empty if the error does not refer to any input, e.g. for
+    /// an empty label or the end of the input.
+    #[inline]
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the tokens that were expected at the position of the error.
+    #[inline]
+    pub fn expected(&self) -> &'static [Token] {
+        self.expected
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "parse error at index {}: {}",
+            self.index(),
+            self.message()
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_display() {
+        let error = ParseError::new(
+            ParseErrorKind::MissingAt,
+            "expected '@'",
+            4..5,
+            &[Token::Char('@')],
+        );
+        assert_eq!(error.to_string(), "parse error at index 4: expected '@'");
+    }
+
+    #[test]
+    fn test_parse_error_invalid_character_span() {
+        let error = ParseError::invalid_character('ä', "invalid character", 3, &[]);
+        assert_eq!(
+            error.kind(),
+            ParseErrorKind::InvalidCharacter { found: 'ä' }
+        );
+        assert_eq!(error.span(), 3..5);
+    }
+}
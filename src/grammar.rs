@@ -0,0 +1,438 @@
+//! The productions of the address grammar as composable building blocks.
+//!
+//! Every function parses a single production of [RFC
+//! 5322](https://www.rfc-editor.org/rfc/rfc5322#section-3.2) at the start of
+//! the input and returns its value together with the rest of the input, so the
+//! exact grammar used by [`AddrSpec`] can be embedded into other parsers.
+//! Error offsets refer to the given input.
+//!
+//! With the `nom` or `winnow` features enabled, the `nom` and `winnow` modules
+//! provide the same productions as parsers of the respective crates.
+//!
+//! # Examples
+//!
+//! ```
+//! use addr_spec::{grammar, ParserOptions};
+//!
+//! let options = ParserOptions::default();
+//!
+//! let (addr_spec, rest) = grammar::addr_spec("jdoe@example.com> SIZE=1000", &options).unwrap();
+//! assert_eq!(addr_spec.to_string(), "jdoe@example.com");
+//! assert_eq!(rest, "> SIZE=1000");
+//!
+//! let (atom, rest) = grammar::dot_atom("john.doe, jane").unwrap();
+//! assert_eq!((atom, rest), ("john.doe", ", jane"));
+//! ```
+
+use std::borrow::Cow;
+
+use super::{parser::Parser, AddrSpec, ParseError, ParserOptions};
+
+/// Parses a `dot-atom`, e.g. `john.doe`.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::grammar;
+///
+/// assert_eq!(grammar::dot_atom("a.b@c").unwrap(), ("a.b", "@c"));
+/// assert!(grammar::dot_atom("a..b").is_err());
+/// ```
+#[inline]
+pub fn dot_atom(input: &str) -> Result<(&str, &str), ParseError> {
+    Parser::new(input).parse_prefix(|parser| parser.parse_dot_atom("empty label in dot-atom"))
+}
+
+/// Parses a `quoted-string` including its quotes and returns its unescaped
+/// content.
+///
+/// Folding white spaces inside the quotes are only accepted if
+/// [`ParserOptions::white_spaces`] is enabled. The content is borrowed from
+/// the input if it contains neither quoted pairs nor white spaces.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{grammar, ParserOptions};
+///
+/// let options = ParserOptions::default();
+/// let (content, rest) = grammar::quoted_string(r#""j\"doe"@c"#, &options).unwrap();
+/// assert_eq!((content.as_ref(), rest), ("j\"doe", "@c"));
+/// ```
+#[inline]
+pub fn quoted_string<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+) -> Result<(Cow<'a, str>, &'a str), ParseError> {
+    Parser::with_options(input, options).parse_prefix(Parser::parse_quoted_string_token)
+}
+
+/// Parses a `domain-literal` including its brackets and returns its content.
+///
+/// Domain literals are accepted regardless of [`ParserOptions::literals`].
+/// Folding white spaces inside the brackets are only accepted if
+/// [`ParserOptions::white_spaces`] is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{grammar, ParserOptions};
+///
+/// let options = ParserOptions::default();
+/// let (content, rest) = grammar::domain_literal("[192.0.2.1]>", &options).unwrap();
+/// assert_eq!((content.as_ref(), rest), ("192.0.2.1", ">"));
+/// ```
+#[inline]
+pub fn domain_literal<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+) -> Result<(Cow<'a, str>, &'a str), ParseError> {
+    Parser::with_options(input, options).parse_prefix(Parser::parse_domain_literal_token)
+}
+
+/// Parses optional `CFWS`, i.e. comments and folding white spaces, and
+/// returns the consumed input.
+///
+/// Folding white spaces and comments are only consumed if
+/// [`ParserOptions::white_spaces`] and [`ParserOptions::comments`] are
+/// enabled respectively, so this may consume nothing at all.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{grammar, ParserOptions};
+///
+/// let mut options = ParserOptions::default();
+/// options.white_spaces = true;
+/// options.comments = true;
+///
+/// let (cfws, rest) = grammar::cfws(" (John Doe) jdoe", &options).unwrap();
+/// assert_eq!((cfws, rest), (" (John Doe) ", "jdoe"));
+/// ```
+#[inline]
+pub fn cfws<'a>(input: &'a str, options: &ParserOptions) -> Result<(&'a str, &'a str), ParseError> {
+    let ((), rest) = Parser::with_options(input, options).parse_prefix(Parser::parse_cfws)?;
+    Ok((&input[..input.len() - rest.len()], rest))
+}
+
+/// Parses an `addr-spec` including surrounding CFWS, like
+/// [`AddrSpec::parse_with`] but stopping at the end of the address.
+#[inline]
+pub fn addr_spec<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+) -> Result<(AddrSpec, &'a str), ParseError> {
+    Parser::with_options(input, options).parse_prefix(Parser::parse_addr_spec)
+}
+
+/// The productions as [`nom`](::nom) parsers.
+///
+/// Each function returns a parser using the given options. Errors are
+/// converted using [`FromExternalError`](::nom::error::FromExternalError) at
+/// the position of the error, so custom error types can keep the original
+/// [`ParseError`].
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{grammar, ParserOptions};
+/// use nom::{bytes::complete::tag, sequence::delimited, Parser};
+///
+/// let mut path = delimited(
+///     tag::<_, _, nom::error::Error<&str>>("<"),
+///     grammar::nom::addr_spec(ParserOptions::default()),
+///     tag(">"),
+/// );
+/// let (rest, addr_spec) = path.parse("<jdoe@example.com> SIZE=1000").unwrap();
+/// assert_eq!(addr_spec.to_string(), "jdoe@example.com");
+/// assert_eq!(rest, " SIZE=1000");
+/// ```
+#[cfg(feature = "nom")]
+pub mod nom {
+    use std::borrow::Cow;
+
+    use ::nom::{
+        error::{ErrorKind, FromExternalError},
+        IResult,
+    };
+
+    use crate::{AddrSpec, ParseError, ParserOptions};
+
+    fn adapt<'a, O, E>(
+        input: &'a str,
+        result: Result<(O, &'a str), ParseError>,
+    ) -> IResult<&'a str, O, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        match result {
+            Ok((value, rest)) => Ok((rest, value)),
+            Err(error) => Err(::nom::Err::Error(E::from_external_error(
+                &input[error.index()..],
+                ErrorKind::Verify,
+                error,
+            ))),
+        }
+    }
+
+    /// Returns a parser for a `dot-atom`. See [`super::dot_atom`].
+    pub fn dot_atom<'a, E>() -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        move |input| adapt(input, super::dot_atom(input))
+    }
+
+    /// Returns a parser for a `quoted-string`. See [`super::quoted_string`].
+    pub fn quoted_string<'a, E>(
+        options: ParserOptions,
+    ) -> impl FnMut(&'a str) -> IResult<&'a str, Cow<'a, str>, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        move |input| adapt(input, super::quoted_string(input, &options))
+    }
+
+    /// Returns a parser for a `domain-literal`. See [`super::domain_literal`].
+    pub fn domain_literal<'a, E>(
+        options: ParserOptions,
+    ) -> impl FnMut(&'a str) -> IResult<&'a str, Cow<'a, str>, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        move |input| adapt(input, super::domain_literal(input, &options))
+    }
+
+    /// Returns a parser for optional `CFWS`. See [`super::cfws`].
+    pub fn cfws<'a, E>(
+        options: ParserOptions,
+    ) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        move |input| adapt(input, super::cfws(input, &options))
+    }
+
+    /// Returns a parser for an `addr-spec`. See [`super::addr_spec`].
+    pub fn addr_spec<'a, E>(
+        options: ParserOptions,
+    ) -> impl FnMut(&'a str) -> IResult<&'a str, AddrSpec, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        move |input| adapt(input, super::addr_spec(input, &options))
+    }
+}
+
+/// The productions as [`winnow`](::winnow) parsers.
+///
+/// Each function returns a parser using the given options. Errors are
+/// converted using [`FromExternalError`](::winnow::error::FromExternalError)
+/// and reported as [`ErrMode::Backtrack`](::winnow::error::ErrMode::Backtrack),
+/// with the input advanced to the position of the error.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{grammar, ParserOptions};
+/// use winnow::{combinator::delimited, error::ContextError, Parser};
+///
+/// let mut input = "<jdoe@example.com> SIZE=1000";
+/// let addr_spec = delimited(
+///     '<',
+///     grammar::winnow::addr_spec::<ContextError>(ParserOptions::default()),
+///     '>',
+/// )
+/// .parse_next(&mut input)
+/// .unwrap();
+/// assert_eq!(addr_spec.to_string(), "jdoe@example.com");
+/// assert_eq!(input, " SIZE=1000");
+/// ```
+#[cfg(feature = "winnow")]
+pub mod winnow {
+    use std::borrow::Cow;
+
+    use ::winnow::{
+        error::{ErrMode, FromExternalError},
+        ModalResult,
+    };
+
+    use crate::{AddrSpec, ParseError, ParserOptions};
+
+    fn adapt<'a, O, E>(
+        input: &mut &'a str,
+        result: Result<(O, &'a str), ParseError>,
+    ) -> ModalResult<O, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        match result {
+            Ok((value, rest)) => {
+                *input = rest;
+                Ok(value)
+            }
+            Err(error) => {
+                *input = &input[error.index()..];
+                Err(ErrMode::Backtrack(E::from_external_error(input, error)))
+            }
+        }
+    }
+
+    /// Returns a parser for a `dot-atom`. See [`super::dot_atom`].
+    pub fn dot_atom<'a, E>() -> impl FnMut(&mut &'a str) -> ModalResult<&'a str, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        move |input| adapt(input, super::dot_atom(input))
+    }
+
+    /// Returns a parser for a `quoted-string`. See [`super::quoted_string`].
+    pub fn quoted_string<'a, E>(
+        options: ParserOptions,
+    ) -> impl FnMut(&mut &'a str) -> ModalResult<Cow<'a, str>, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        move |input| adapt(input, super::quoted_string(input, &options))
+    }
+
+    /// Returns a parser for a `domain-literal`. See [`super::domain_literal`].
+    pub fn domain_literal<'a, E>(
+        options: ParserOptions,
+    ) -> impl FnMut(&mut &'a str) -> ModalResult<Cow<'a, str>, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        move |input| adapt(input, super::domain_literal(input, &options))
+    }
+
+    /// Returns a parser for optional `CFWS`. See [`super::cfws`].
+    pub fn cfws<'a, E>(
+        options: ParserOptions,
+    ) -> impl FnMut(&mut &'a str) -> ModalResult<&'a str, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        move |input| adapt(input, super::cfws(input, &options))
+    }
+
+    /// Returns a parser for an `addr-spec`. See [`super::addr_spec`].
+    pub fn addr_spec<'a, E>(
+        options: ParserOptions,
+    ) -> impl FnMut(&mut &'a str) -> ModalResult<AddrSpec, E>
+    where
+        E: FromExternalError<&'a str, ParseError>,
+    {
+        move |input| adapt(input, super::addr_spec(input, &options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParseErrorKind, Profile, Token};
+
+    #[test]
+    fn test_dot_atom() {
+        assert_eq!(dot_atom("john.doe").unwrap(), ("john.doe", ""));
+        assert_eq!(
+            dot_atom(".doe").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::EmptyLabel,
+                "empty label in dot-atom",
+                0..0,
+                &[Token::Atext]
+            )
+        );
+    }
+
+    #[test]
+    fn test_quoted_string() {
+        let (content, rest) = quoted_string("\"john@doe\" x", &ParserOptions::new()).unwrap();
+        assert_eq!((content.as_ref(), rest), ("john@doe", " x"));
+        assert_eq!(
+            quoted_string("john", &ParserOptions::new()).unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: 'j' },
+                "expected '\"' for quoted string",
+                0..1,
+                &[Token::Char('"')]
+            )
+        );
+        assert_eq!(
+            quoted_string("\"john", &ParserOptions::new())
+                .unwrap_err()
+                .kind(),
+            ParseErrorKind::UnterminatedQuote
+        );
+    }
+
+    #[test]
+    fn test_domain_literal() {
+        let (content, rest) = domain_literal("[ 192.0.2.1 ]", &Profile::Rfc5322.into()).unwrap();
+        assert_eq!((content.as_ref(), rest), ("192.0.2.1", ""));
+        assert_eq!(
+            domain_literal("", &ParserOptions::new()).unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::UnexpectedEnd,
+                "expected '[' for domain literal",
+                0..0,
+                &[Token::Char('[')]
+            )
+        );
+    }
+
+    #[test]
+    fn test_cfws() {
+        assert_eq!(
+            cfws("jdoe", &Profile::Rfc5322.into()).unwrap(),
+            ("", "jdoe")
+        );
+        assert_eq!(
+            cfws("(a (b)) \r\n x", &Profile::Rfc5322.into()).unwrap(),
+            ("(a (b)) \r\n ", "x")
+        );
+        assert_eq!(
+            cfws("(a", &Profile::Rfc5322.into()).unwrap_err().kind(),
+            ParseErrorKind::UnterminatedComment
+        );
+    }
+
+    #[test]
+    fn test_addr_spec() {
+        let (addr_spec, rest) =
+            addr_spec("jdoe@example.com (x), jane", &Profile::Rfc5322.into()).unwrap();
+        assert_eq!(addr_spec, AddrSpec::new("jdoe", "example.com").unwrap());
+        assert_eq!(rest, ", jane");
+        assert_eq!(
+            super::addr_spec("jdoe@", &Profile::Rfc5322.into())
+                .unwrap_err()
+                .span(),
+            5..5
+        );
+    }
+
+    #[cfg(feature = "nom")]
+    #[test]
+    fn test_nom_error() {
+        use ::nom::{error::Error, Parser};
+
+        let error = nom::dot_atom::<Error<&str>>().parse("a..b").unwrap_err();
+        assert_eq!(
+            error,
+            ::nom::Err::Error(Error::new(".b", ::nom::error::ErrorKind::Verify))
+        );
+    }
+
+    #[cfg(feature = "winnow")]
+    #[test]
+    fn test_winnow_error() {
+        use ::winnow::{error::ContextError, Parser};
+
+        let mut input = "a..b";
+        assert!(winnow::dot_atom::<ContextError>()
+            .parse_next(&mut input)
+            .is_err());
+        assert_eq!(input, ".b");
+    }
+}
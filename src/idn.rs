@@ -0,0 +1,653 @@
+use std::{borrow::Cow, iter, ops::Range};
+
+use icu_normalizer::uts46::Uts46MapperBorrowed;
+use icu_properties::{
+    props::{
+        BidiClass, CanonicalCombiningClass, DefaultIgnorableCodePoint, GeneralCategory,
+        HangulSyllableType, JoiningType, NoncharacterCodePoint, Script, WhiteSpace,
+    },
+    CodePointMapData, CodePointSetData,
+};
+
+use super::{ParseError, ParseErrorKind, Token};
+
+const MAPPER: Uts46MapperBorrowed<'static> = Uts46MapperBorrowed::new();
+
+/// The derived property value of a code point as defined in [RFC
+/// 5892](https://www.rfc-editor.org/rfc/rfc5892#section-2).
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+enum Property {
+    Pvalid,
+    ContextJ,
+    ContextO,
+    Disallowed,
+}
+
+/// A label of a domain after mapping, in both forms.
+struct Label {
+    ascii: String,
+    unicode: String,
+    span: Range<usize>,
+}
+
+/// Converts the domain into its ASCII form, i.e. maps it as defined in UTS #46
+/// and converts its U-labels into A-labels.
+///
+/// The spans of errors are relative to the domain.
+pub fn domain_to_ascii(domain: &str) -> Result<Cow<'_, str>, ParseError> {
+    let labels = process(domain)?;
+    Ok(join(
+        domain,
+        labels.iter().map(|label| label.ascii.as_str()),
+    ))
+}
+
+/// Converts the domain of a hostname into its ASCII form like
+/// [`domain_to_ascii`], but only folds the case of non-ASCII characters instead
+/// of mapping them as defined in UTS #46, i.e. e.g. full-width forms and
+/// ideographic full stops are disallowed as in IDNA2008.
+///
+/// The spans of errors are relative to the domain.
+pub fn hostname_to_ascii(domain: &str) -> Result<Cow<'_, str>, ParseError> {
+    if let Some((index, chr)) = domain.char_indices().find(|&(_, chr)| {
+        !chr.is_ascii() && !MAPPER.map_normalize(iter::once(chr)).eq(chr.to_lowercase())
+    }) {
+        return Err(disallowed(chr, index..index + chr.len_utf8()));
+    }
+    domain_to_ascii(domain)
+}
+
+/// Converts the domain into its Unicode form, i.e. maps it as defined in UTS
+/// #46 and converts its A-labels into U-labels.
+///
+/// The spans of errors are relative to the domain.
+pub fn domain_to_unicode(domain: &str) -> Result<Cow<'_, str>, ParseError> {
+    let labels = process(domain)?;
+    Ok(join(
+        domain,
+        labels.iter().map(|label| label.unicode.as_str()),
+    ))
+}
+
+fn join<'a, 'b>(domain: &'a str, mut labels: impl Iterator<Item = &'b str>) -> Cow<'a, str> {
+    let mut result = String::with_capacity(domain.len());
+    if let Some(label) = labels.next() {
+        result.push_str(label);
+    }
+    for label in labels {
+        result.push('.');
+        result.push_str(label);
+    }
+    if result == domain {
+        Cow::Borrowed(domain)
+    } else {
+        Cow::Owned(result)
+    }
+}
+
+/// Maps, decodes and validates the labels of the domain.
+fn process(domain: &str) -> Result<Vec<Label>, ParseError> {
+    let mut labels = Vec::new();
+    let mut start = 0;
+    for label in domain.split('.') {
+        let span = start..start + label.len();
+        start = span.end + 1;
+        let mapped = if label.is_ascii() {
+            label.to_ascii_lowercase()
+        } else {
+            MAPPER.map_normalize(label.chars()).collect::<String>()
+        };
+        if mapped.contains('\u{fffd}') {
+            let found = label
+                .chars()
+                .find(|&chr| {
+                    MAPPER
+                        .map_normalize(iter::once(chr))
+                        .any(|chr| chr == '\u{fffd}')
+                })
+                .unwrap_or('\u{fffd}');
+            return Err(disallowed(found, span));
+        }
+        // Full stops such as U+3002 are mapped to periods.
+        for label in mapped.split('.') {
+            labels.push(decode(label, span.clone())?);
+        }
+    }
+
+    let bidi = labels.iter().any(|label| {
+        label.unicode.chars().any(|chr| {
+            matches!(
+                bidi_class(chr),
+                BidiClass::RightToLeft | BidiClass::ArabicLetter | BidiClass::ArabicNumber
+            )
+        })
+    });
+    for label in &labels {
+        validate(label, bidi)?;
+    }
+
+    let len = labels
+        .iter()
+        .map(|label| label.ascii.len() + 1)
+        .sum::<usize>()
+        - 1;
+    if len > 255 {
+        return Err(ParseError::new(
+            ParseErrorKind::DomainTooLong,
+            "domain longer than 255 octets",
+            0..domain.len(),
+            &[],
+        ));
+    }
+    Ok(labels)
+}
+
+/// Converts a mapped label into both forms.
+fn decode(label: &str, span: Range<usize>) -> Result<Label, ParseError> {
+    if label.is_empty() {
+        return Err(ParseError::new(
+            ParseErrorKind::EmptyLabel,
+            "empty label in domain",
+            span,
+            &[Token::LetterOrDigit],
+        ));
+    }
+    let (ascii, unicode) = if let Some(encoded) = label.strip_prefix("xn--") {
+        let unicode = idna::punycode::decode_to_string(encoded)
+            .filter(|unicode| {
+                !unicode.is_ascii()
+                    && MAPPER.map_normalize(unicode.chars()).eq(unicode.chars())
+                    && idna::punycode::encode_str(unicode).as_deref() == Some(encoded)
+            })
+            .ok_or_else(|| {
+                ParseError::new(
+                    ParseErrorKind::InvalidALabel,
+                    "invalid A-label",
+                    span.clone(),
+                    &[],
+                )
+            })?;
+        (label.to_owned(), unicode)
+    } else if label.is_ascii() {
+        (label.to_owned(), label.to_owned())
+    } else {
+        let ascii = idna::punycode::encode_str(label)
+            .map(|encoded| format!("xn--{encoded}"))
+            .unwrap_or_default();
+        (ascii, label.to_owned())
+    };
+    if ascii.is_empty() || ascii.len() > 63 {
+        return Err(ParseError::new(
+            ParseErrorKind::LabelTooLong,
+            "label longer than 63 octets",
+            span,
+            &[],
+        ));
+    }
+    Ok(Label {
+        ascii,
+        unicode,
+        span,
+    })
+}
+
+/// Validates a label as defined in [Section 5.4, RFC
+/// 5891](https://www.rfc-editor.org/rfc/rfc5891#section-5.4).
+fn validate(label: &Label, bidi: bool) -> Result<(), ParseError> {
+    let span = || label.span.clone();
+    let chars = label.unicode.chars().collect::<Vec<_>>();
+
+    if chars.first() == Some(&'-') || chars.last() == Some(&'-') {
+        return Err(ParseError::new(
+            ParseErrorKind::InvalidCharacter { found: '-' },
+            "invalid hyphen in label",
+            span(),
+            &[Token::LetterOrDigit],
+        ));
+    }
+    if !label.unicode.is_ascii() && chars.get(2..4) == Some(&['-', '-'][..]) {
+        return Err(ParseError::new(
+            ParseErrorKind::InvalidCharacter { found: '-' },
+            "hyphens in the third and fourth position of label",
+            span(),
+            &[Token::LetterOrDigit],
+        ));
+    }
+    if let Some(&chr) = chars.first().filter(|&&chr| is_mark(chr)) {
+        return Err(ParseError::new(
+            ParseErrorKind::InvalidCharacter { found: chr },
+            "label starts with a combining mark",
+            span(),
+            &[Token::LetterOrDigit],
+        ));
+    }
+
+    for (index, &chr) in chars.iter().enumerate() {
+        let valid = match property(chr) {
+            Property::Pvalid => true,
+            Property::ContextJ => is_valid_context_j(&chars, index),
+            Property::ContextO => is_valid_context_o(&chars, index),
+            Property::Disallowed if label.unicode.is_ascii() => {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: chr },
+                    "invalid character in label",
+                    span(),
+                    &[Token::LetterOrDigit, Token::Char('-')],
+                ));
+            }
+            Property::Disallowed => return Err(disallowed(chr, span())),
+        };
+        if !valid {
+            return Err(ParseError::new(
+                ParseErrorKind::ContextRule { found: chr },
+                "contextual rule not satisfied",
+                span(),
+                &[],
+            ));
+        }
+    }
+
+    if bidi && !satisfies_bidi_rule(&chars) {
+        return Err(ParseError::new(
+            ParseErrorKind::BidiRule,
+            "label does not satisfy the bidi rule",
+            span(),
+            &[],
+        ));
+    }
+    Ok(())
+}
+
+fn disallowed(found: char, span: Range<usize>) -> ParseError {
+    ParseError::new(
+        ParseErrorKind::DisallowedCodePoint { found },
+        "disallowed code point in domain",
+        span,
+        &[],
+    )
+}
+
+/// Returns the derived property value of a mapped code point.
+fn property(chr: char) -> Property {
+    match chr {
+        // Exceptions (F)
+        '\u{df}' | '\u{3c2}' | '\u{6fd}' | '\u{6fe}' | '\u{f0b}' | '\u{3007}' => {
+            return Property::Pvalid
+        }
+        '\u{b7}' | '\u{375}' | '\u{5f3}' | '\u{5f4}' | '\u{30fb}' => return Property::ContextO,
+        '\u{660}'..='\u{669}' | '\u{6f0}'..='\u{6f9}' => return Property::ContextO,
+        '\u{640}' | '\u{7fa}' | '\u{302e}' | '\u{302f}' | '\u{3031}'..='\u{3035}' | '\u{303b}' => {
+            return Property::Disallowed
+        }
+        // LDH (J)
+        'a'..='z' | '0'..='9' | '-' => return Property::Pvalid,
+        _ if chr.is_ascii() => return Property::Disallowed,
+        // JoinControl (H)
+        '\u{200c}' | '\u{200d}' => return Property::ContextJ,
+        _ => {}
+    }
+    let general_category = CodePointMapData::<GeneralCategory>::new().get(chr);
+    // Unassigned (J), Unstable (B), IgnorableProperties (C) and OldHangulJamo (I)
+    if general_category == GeneralCategory::Unassigned
+        || !MAPPER.map_normalize(iter::once(chr)).eq(iter::once(chr))
+        || CodePointSetData::new::<DefaultIgnorableCodePoint>().contains(chr)
+        || CodePointSetData::new::<WhiteSpace>().contains(chr)
+        || CodePointSetData::new::<NoncharacterCodePoint>().contains(chr)
+        || matches!(
+            CodePointMapData::<HangulSyllableType>::new().get(chr),
+            HangulSyllableType::LeadingJamo
+                | HangulSyllableType::VowelJamo
+                | HangulSyllableType::TrailingJamo
+        )
+    {
+        return Property::Disallowed;
+    }
+    // LetterDigits (A)
+    match general_category {
+        GeneralCategory::LowercaseLetter
+        | GeneralCategory::UppercaseLetter
+        | GeneralCategory::OtherLetter
+        | GeneralCategory::DecimalNumber
+        | GeneralCategory::ModifierLetter
+        | GeneralCategory::NonspacingMark
+        | GeneralCategory::SpacingMark => Property::Pvalid,
+        _ => Property::Disallowed,
+    }
+}
+
+fn is_mark(chr: char) -> bool {
+    matches!(
+        CodePointMapData::<GeneralCategory>::new().get(chr),
+        GeneralCategory::NonspacingMark
+            | GeneralCategory::SpacingMark
+            | GeneralCategory::EnclosingMark
+    )
+}
+
+fn is_virama(chr: char) -> bool {
+    CodePointMapData::<CanonicalCombiningClass>::new().get(chr) == CanonicalCombiningClass::Virama
+}
+
+fn joining_type(chr: char) -> JoiningType {
+    CodePointMapData::<JoiningType>::new().get(chr)
+}
+
+fn script(chr: char) -> Script {
+    CodePointMapData::<Script>::new().get(chr)
+}
+
+fn bidi_class(chr: char) -> BidiClass {
+    CodePointMapData::<BidiClass>::new().get(chr)
+}
+
+/// Evaluates the CONTEXTJ rules of [Appendix A, RFC
+/// 5892](https://www.rfc-editor.org/rfc/rfc5892#appendix-A).
+fn is_valid_context_j(chars: &[char], index: usize) -> bool {
+    let (before, after) = (&chars[..index], &chars[index + 1..]);
+    if before.last().is_some_and(|&chr| is_virama(chr)) {
+        return true;
+    }
+    if chars[index] != '\u{200c}' {
+        return false;
+    }
+    let is_transparent = |chr: &&char| joining_type(**chr) == JoiningType::Transparent;
+    let left = before.iter().rev().find(|chr| !is_transparent(chr));
+    let right = after.iter().find(|chr| !is_transparent(chr));
+    left.is_some_and(|&chr| {
+        matches!(
+            joining_type(chr),
+            JoiningType::LeftJoining | JoiningType::DualJoining
+        )
+    }) && right.is_some_and(|&chr| {
+        matches!(
+            joining_type(chr),
+            JoiningType::RightJoining | JoiningType::DualJoining
+        )
+    })
+}
+
+/// Evaluates the CONTEXTO rules of [Appendix A, RFC
+/// 5892](https://www.rfc-editor.org/rfc/rfc5892#appendix-A).
+fn is_valid_context_o(chars: &[char], index: usize) -> bool {
+    let before = index.checked_sub(1).map(|index| chars[index]);
+    let after = chars.get(index + 1).copied();
+    match chars[index] {
+        '\u{b7}' => before == Some('l') && after == Some('l'),
+        '\u{375}' => after.is_some_and(|chr| script(chr) == Script::Greek),
+        '\u{5f3}' | '\u{5f4}' => before.is_some_and(|chr| script(chr) == Script::Hebrew),
+        '\u{30fb}' => chars.iter().any(|&chr| {
+            matches!(
+                script(chr),
+                Script::Hiragana | Script::Katakana | Script::Han
+            )
+        }),
+        '\u{660}'..='\u{669}' => !chars.iter().any(|chr| matches!(chr, '\u{6f0}'..='\u{6f9}')),
+        '\u{6f0}'..='\u{6f9}' => !chars.iter().any(|chr| matches!(chr, '\u{660}'..='\u{669}')),
+        _ => false,
+    }
+}
+
+/// Evaluates the Bidi rule of [Section 2, RFC
+/// 5893](https://www.rfc-editor.org/rfc/rfc5893#section-2).
+fn satisfies_bidi_rule(chars: &[char]) -> bool {
+    let Some(&first) = chars.first() else {
+        return true;
+    };
+    let classes = chars.iter().map(|&chr| bidi_class(chr)).collect::<Vec<_>>();
+    let last = classes
+        .iter()
+        .rev()
+        .find(|&&class| class != BidiClass::NonspacingMark);
+    match bidi_class(first) {
+        BidiClass::RightToLeft | BidiClass::ArabicLetter => {
+            classes.iter().all(|&class| {
+                matches!(
+                    class,
+                    BidiClass::RightToLeft
+                        | BidiClass::ArabicLetter
+                        | BidiClass::ArabicNumber
+                        | BidiClass::EuropeanNumber
+                        | BidiClass::EuropeanSeparator
+                        | BidiClass::CommonSeparator
+                        | BidiClass::EuropeanTerminator
+                        | BidiClass::OtherNeutral
+                        | BidiClass::BoundaryNeutral
+                        | BidiClass::NonspacingMark
+                )
+            }) && last.is_some_and(|&class| {
+                matches!(
+                    class,
+                    BidiClass::RightToLeft
+                        | BidiClass::ArabicLetter
+                        | BidiClass::EuropeanNumber
+                        | BidiClass::ArabicNumber
+                )
+            }) && !(classes.contains(&BidiClass::EuropeanNumber)
+                && classes.contains(&BidiClass::ArabicNumber))
+        }
+        BidiClass::LeftToRight => {
+            classes.iter().all(|&class| {
+                matches!(
+                    class,
+                    BidiClass::LeftToRight
+                        | BidiClass::EuropeanNumber
+                        | BidiClass::EuropeanSeparator
+                        | BidiClass::CommonSeparator
+                        | BidiClass::EuropeanTerminator
+                        | BidiClass::OtherNeutral
+                        | BidiClass::BoundaryNeutral
+                        | BidiClass::NonspacingMark
+                )
+            }) && last.is_some_and(|&class| {
+                matches!(class, BidiClass::LeftToRight | BidiClass::EuropeanNumber)
+            })
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_to_ascii() {
+        for (domain, expected) in [
+            ("example.com", "example.com"),
+            ("Example.COM", "example.com"),
+            ("m\u{fc}nchen.de", "xn--mnchen-3ya.de"),
+            ("M\u{dc}NCHEN.de", "xn--mnchen-3ya.de"),
+            ("xn--mnchen-3ya.de", "xn--mnchen-3ya.de"),
+            (
+                "\u{4f8b}\u{3048}\u{3002}\u{30c6}\u{30b9}\u{30c8}",
+                "xn--r8jz45g.xn--zckzah",
+            ),
+            ("fa\u{df}.de", "xn--fa-hia.de"),
+            ("\u{5d0}\u{5d1}.example", "xn--4dbc.example"),
+        ] {
+            assert_eq!(domain_to_ascii(domain).unwrap(), expected, "{domain:?}");
+        }
+        assert!(matches!(
+            domain_to_ascii("example.com").unwrap(),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_hostname_to_ascii() {
+        assert_eq!(
+            hostname_to_ascii("M\u{dc}NCHEN.de").unwrap(),
+            "xn--mnchen-3ya.de"
+        );
+        for (domain, found, span) in [
+            ("\u{ff25}\u{ff38}.com", '\u{ff25}', 0..3),
+            ("a\u{3002}com", '\u{3002}', 1..4),
+            ("\u{fb01}sh.com", '\u{fb01}', 0..3),
+        ] {
+            let error = hostname_to_ascii(domain).unwrap_err();
+            assert_eq!(
+                error.kind(),
+                ParseErrorKind::DisallowedCodePoint { found },
+                "{domain:?}"
+            );
+            assert_eq!(error.span(), span, "{domain:?}");
+        }
+    }
+
+    #[test]
+    fn test_domain_to_unicode() {
+        for (domain, expected) in [
+            ("example.com", "example.com"),
+            ("xn--mnchen-3ya.de", "m\u{fc}nchen.de"),
+            ("XN--MNCHEN-3YA.DE", "m\u{fc}nchen.de"),
+            ("m\u{fc}nchen.de", "m\u{fc}nchen.de"),
+        ] {
+            assert_eq!(domain_to_unicode(domain).unwrap(), expected, "{domain:?}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_a_labels() {
+        for domain in [
+            "xn--.de",
+            "xn--abc-.de",
+            "xn--ls8h-.de",
+            "xn--mnchen-3yb-.de",
+        ] {
+            assert_eq!(
+                domain_to_ascii(domain).unwrap_err().kind(),
+                ParseErrorKind::InvalidALabel,
+                "{domain:?}"
+            );
+        }
+        // Decodes into an uppercase letter, which is not a mapped form.
+        assert_eq!(
+            domain_to_ascii("xn--mnchen-psa.de").unwrap_err(),
+            ParseError::new(ParseErrorKind::InvalidALabel, "invalid A-label", 0..14, &[])
+        );
+    }
+
+    #[test]
+    fn test_disallowed_code_points() {
+        for (domain, found) in [
+            ("\u{2603}.example", '\u{2603}'),
+            ("a\u{e000}.example", '\u{e000}'),
+            ("a\u{640}b.example", '\u{640}'),
+            ("a\u{1100}.example", '\u{1100}'),
+        ] {
+            assert_eq!(
+                domain_to_ascii(domain).unwrap_err().kind(),
+                ParseErrorKind::DisallowedCodePoint { found },
+                "{domain:?}"
+            );
+        }
+        assert_eq!(
+            domain_to_ascii("example.a\u{2603}").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::DisallowedCodePoint { found: '\u{2603}' },
+                "disallowed code point in domain",
+                8..12,
+                &[]
+            )
+        );
+    }
+
+    #[test]
+    fn test_context_rules() {
+        for domain in [
+            "l\u{b7}l.example",
+            "\u{915}\u{94d}\u{200d}.example",
+            "\u{628}\u{200c}\u{628}.example",
+            "\u{375}\u{3b1}.example",
+            "\u{5d0}\u{5f3}.example",
+            "\u{30a2}\u{30fb}.example",
+            "\u{628}\u{660}.example",
+        ] {
+            assert!(domain_to_ascii(domain).is_ok(), "{domain:?}");
+        }
+        for (domain, found) in [
+            ("a\u{b7}l.example", '\u{b7}'),
+            ("a\u{200d}b.example", '\u{200d}'),
+            ("a\u{200c}b.example", '\u{200c}'),
+            ("\u{375}a.example", '\u{375}'),
+            ("a\u{5f3}.example", '\u{5f3}'),
+            ("\u{e9}\u{30fb}.example", '\u{30fb}'),
+            ("\u{628}\u{660}\u{6f0}.example", '\u{660}'),
+        ] {
+            assert_eq!(
+                domain_to_ascii(domain).unwrap_err().kind(),
+                ParseErrorKind::ContextRule { found },
+                "{domain:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_bidi_rule() {
+        for domain in [
+            "\u{5d0}\u{5d1}.com",
+            "\u{5d0}1.com",
+            "a\u{5d0}.com",
+            "1\u{5d0}.com",
+        ] {
+            let result = domain_to_ascii(domain);
+            if domain.starts_with('\u{5d0}') {
+                assert!(result.is_ok(), "{domain:?}");
+            } else {
+                assert_eq!(
+                    result.unwrap_err().kind(),
+                    ParseErrorKind::BidiRule,
+                    "{domain:?}"
+                );
+            }
+        }
+        // In a bidi domain, every label must satisfy the rule.
+        assert_eq!(
+            domain_to_ascii("1a.\u{5d0}.com").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::BidiRule,
+                "label does not satisfy the bidi rule",
+                0..2,
+                &[]
+            )
+        );
+        assert!(domain_to_ascii("1a.example.com").is_ok());
+    }
+
+    #[test]
+    fn test_hyphens() {
+        for domain in [
+            "-a.example",
+            "a-.example",
+            "\u{e9}b--c.example",
+            "\u{301}a.example",
+        ] {
+            assert!(
+                matches!(
+                    domain_to_ascii(domain).unwrap_err().kind(),
+                    ParseErrorKind::InvalidCharacter { .. }
+                ),
+                "{domain:?}"
+            );
+        }
+        assert!(domain_to_ascii("ab--cd.example").is_ok());
+    }
+
+    #[test]
+    fn test_lengths() {
+        let label = format!("\u{fc}{}", "a".repeat(60));
+        assert_eq!(
+            domain_to_ascii(&format!("{label}.de")).unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::LabelTooLong,
+                "label longer than 63 octets",
+                0..62,
+                &[]
+            )
+        );
+        assert_eq!(
+            domain_to_ascii("a..b").unwrap_err().kind(),
+            ParseErrorKind::EmptyLabel
+        );
+    }
+}
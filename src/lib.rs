@@ -1,22 +1,97 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(feature = "nightly", feature(test))]
 
+mod addr_spec_ref;
+mod address;
 mod ascii;
+mod charset;
+mod diagnostic;
+mod domain_class;
+mod domain_literal;
+mod error;
+pub mod grammar;
+#[cfg(feature = "idna")]
+mod idn;
+mod mailbox;
 mod parser;
+#[cfg(feature = "precis")]
+mod precis;
+mod prefix;
+mod profile;
+#[cfg(feature = "security")]
+mod restriction_level;
+mod smtp;
+mod syntax;
 mod unicode;
 
 use std::{
+    borrow::Cow,
     fmt::{self, Write},
     str::FromStr,
 };
 
-pub use parser::ParseError;
-use parser::{is_ascii_control_and_not_htab, is_not_atext, is_not_dtext, Parser};
+pub use addr_spec_ref::AddrSpecRef;
+pub use address::{Address, AddressList, Group};
+pub use charset::Charset;
+pub use diagnostic::{Diagnostic, DiagnosticKind, Severity};
+pub use domain_class::DomainClass;
+pub use domain_literal::{DomainLiteral, IpClass};
+pub use error::{ParseError, ParseErrorKind, Token};
+pub use mailbox::Mailbox;
+pub use parser::ParserOptions;
+use parser::{is_ascii_control_and_not_htab, is_not_atext, Parser};
+#[cfg(feature = "precis")]
+pub use precis::PrecisProfile;
+pub use prefix::PrefixCheck;
+pub use profile::Profile;
+#[cfg(feature = "security")]
+pub use restriction_level::RestrictionLevel;
+pub use smtp::{EsmtpParameter, MailCommand, RcptCommand, SmtpPath};
+pub use syntax::{Comment, CommentPosition, SyntaxKind, SyntaxNode, SyntaxTree};
 
 fn quote(value: &str) -> String {
     ascii::escape!(value, b'\\', b'"' | b' ' | b'\t')
 }
 
+/// Returns whether the local part must be quoted when serialized.
+fn is_quoted(local_part: &str) -> bool {
+    local_part
+        .split('.')
+        .any(|s| s.is_empty() || s.contains(is_not_atext))
+}
+
+/// Writes the serialized form of an address specification.
+fn write_addr_spec(
+    formatter: &mut fmt::Formatter<'_>,
+    local_part: &str,
+    domain: &str,
+    literal: bool,
+) -> fmt::Result {
+    if !is_quoted(local_part) {
+        formatter.write_str(local_part)?;
+    } else {
+        formatter.write_char('"')?;
+        for chr in quote(local_part).chars() {
+            formatter.write_char(chr)?;
+        }
+        formatter.write_char('"')?;
+    }
+
+    formatter.write_char('@')?;
+
+    if !literal {
+        formatter.write_str(domain)?;
+    } else {
+        formatter.write_char('[')?;
+        for chr in domain.chars() {
+            formatter.write_char(chr)?;
+        }
+        formatter.write_char(']')?;
+    }
+
+    Ok(())
+}
+
 /// Address specification as defined in [RFC
 /// 5322](https://tools.ietf.org/html/rfc5322#section-3.4.1) with UTF-8 support
 /// as defined in [RFC 6532](https://tools.ietf.org/html/rfc6532).
@@ -105,7 +180,6 @@ fn quote(value: &str) -> String {
 pub struct AddrSpec {
     local_part: String,
     domain: String,
-    #[cfg(feature = "literals")]
     literal: bool,
 }
 
@@ -135,6 +209,182 @@ impl AddrSpec {
         Ok(address.as_ref().parse::<Self>()?.to_string())
     }
 
+    /// Parses an address specification using the given options instead of the
+    /// defaults determined by the enabled features.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, ParserOptions};
+    ///
+    /// let mut strict = ParserOptions::default();
+    /// strict.white_spaces = false;
+    /// strict.comments = false;
+    /// strict.literals = false;
+    /// assert!(AddrSpec::parse_with("jdoe@[machine.example]", &strict).is_err());
+    ///
+    /// let mut lenient = ParserOptions::default();
+    /// lenient.white_spaces = true;
+    /// lenient.comments = true;
+    /// lenient.literals = true;
+    /// let addr_spec = AddrSpec::parse_with(" jdoe (John Doe) @[machine.example]", &lenient).unwrap();
+    /// assert_eq!(addr_spec.to_string(), "jdoe@[machine.example]");
+    /// ```
+    #[inline]
+    pub fn parse_with(address: &str, options: &ParserOptions) -> Result<Self, ParseError> {
+        Parser::with_options(address, options).parse()
+    }
+
+    /// Parses an address specification at the start of the input using the
+    /// default options and returns it together with the rest of the input.
+    ///
+    /// Parsing stops at the end of the address, including any trailing CFWS,
+    /// so the address can be followed by arbitrary input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::AddrSpec;
+    ///
+    /// let (addr_spec, rest) = AddrSpec::parse_prefix("jdoe@example.com>, jane").unwrap();
+    /// assert_eq!(addr_spec.to_string(), "jdoe@example.com");
+    /// assert_eq!(rest, ">, jane");
+    /// ```
+    #[inline]
+    pub fn parse_prefix(address: &str) -> Result<(Self, &str), ParseError> {
+        Self::parse_prefix_with(address, &ParserOptions::new())
+    }
+
+    /// Parses an address specification at the start of the input using the
+    /// given options and returns it together with the rest of the input.
+    #[inline]
+    pub fn parse_prefix_with<'a>(
+        address: &'a str,
+        options: &ParserOptions,
+    ) -> Result<(Self, &'a str), ParseError> {
+        grammar::addr_spec(address, options)
+    }
+
+    /// Checks whether the input is a valid address specification or can still
+    /// become one by appending more input, using the default options.
+    ///
+    /// An input is only reported as [`PrefixCheck::Invalid`] if the error
+    /// cannot be fixed by typing further, which makes this suitable for
+    /// validating input as it is typed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, PrefixCheck};
+    ///
+    /// assert_eq!(AddrSpec::check_prefix("jdoe@"), PrefixCheck::IncompleteButViable);
+    ///
+    /// let PrefixCheck::Invalid(error) = AddrSpec::check_prefix("jd..") else {
+    ///     panic!("expected an invalid prefix");
+    /// };
+    /// assert_eq!(error.index(), 3);
+    /// ```
+    #[inline]
+    pub fn check_prefix(address: &str) -> PrefixCheck {
+        Self::check_prefix_with(address, &ParserOptions::new())
+    }
+
+    /// Checks whether the input is a valid address specification or can still
+    /// become one by appending more input, using the given options.
+    #[inline]
+    pub fn check_prefix_with(address: &str, options: &ParserOptions) -> PrefixCheck {
+        Parser::with_options(address, options).check_prefix()
+    }
+
+    /// Parses an address specification from bytes using the default options.
+    ///
+    /// The bytes must be valid UTF-8. Otherwise, an error of kind
+    /// [`ParseErrorKind::InvalidUtf8`] is returned at the byte offset of the
+    /// first invalid sequence, unless the address is invalid before it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, ParseErrorKind};
+    ///
+    /// let addr_spec = AddrSpec::parse_bytes(b"jdoe@example.com").unwrap();
+    /// assert_eq!(addr_spec.to_string(), "jdoe@example.com");
+    ///
+    /// let error = AddrSpec::parse_bytes(b"jd\xffoe@example.com").unwrap_err();
+    /// assert_eq!(error.kind(), ParseErrorKind::InvalidUtf8);
+    /// assert_eq!(error.index(), 2);
+    /// ```
+    #[inline]
+    pub fn parse_bytes(address: &[u8]) -> Result<Self, ParseError> {
+        Self::parse_bytes_with(address, &ParserOptions::new())
+    }
+
+    /// Parses an address specification from bytes using the given options.
+    ///
+    /// Bytes that are not valid UTF-8 are decoded using
+    /// [`ParserOptions::fallback_charset`] if set. Error offsets always refer
+    /// to the given bytes.
+    #[inline]
+    pub fn parse_bytes_with(address: &[u8], options: &ParserOptions) -> Result<Self, ParseError> {
+        charset::parse_bytes(address, options, Self::parse_with)
+    }
+
+    /// Parses an address specification like [`AddrSpec::parse_with`] and
+    /// additionally returns diagnostics about forms that are valid but
+    /// suspicious, e.g. quoted local parts, comments or domain literals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, DiagnosticKind, ParserOptions, Severity};
+    ///
+    /// let options = ParserOptions::default();
+    /// let (_, diagnostics) = AddrSpec::parse_with_diagnostics("jdoe@example.com", &options).unwrap();
+    /// assert!(diagnostics.is_empty());
+    ///
+    /// let (addr_spec, diagnostics) =
+    ///     AddrSpec::parse_with_diagnostics("\"j.doe\"@example.com", &options).unwrap();
+    /// assert_eq!(addr_spec.to_string(), "j.doe@example.com");
+    /// assert_eq!(diagnostics[0].kind(), DiagnosticKind::QuotedLocalPart);
+    /// assert_eq!(diagnostics[0].severity(), Severity::Rfc5321);
+    /// ```
+    #[inline]
+    pub fn parse_with_diagnostics(
+        address: &str,
+        options: &ParserOptions,
+    ) -> Result<(Self, Vec<Diagnostic>), ParseError> {
+        Parser::with_options(address, options).parse_with_diagnostics()
+    }
+
+    /// Parses an address specification like [`AddrSpec::parse_with`] and
+    /// additionally returns its decoded comments in input order.
+    ///
+    /// Comments are only accepted if [`ParserOptions::comments`] is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, CommentPosition, ParserOptions};
+    ///
+    /// let mut options = ParserOptions::default();
+    /// options.white_spaces = true;
+    /// options.comments = true;
+    ///
+    /// let (addr_spec, comments) =
+    ///     AddrSpec::parse_with_comments("jdoe@example.com (John Doe)", &options).unwrap();
+    /// assert_eq!(addr_spec.to_string(), "jdoe@example.com");
+    /// assert_eq!(comments[0].text(), "John Doe");
+    /// assert_eq!(comments[0].position(), CommentPosition::AfterDomain);
+    /// ```
+    pub fn parse_with_comments(
+        address: &str,
+        options: &ParserOptions,
+    ) -> Result<(Self, Vec<Comment>), ParseError> {
+        let tree = SyntaxTree::parse_with(address, options)?;
+        let comments = tree.comments();
+        Ok((tree.into_addr_spec(), comments))
+    }
+
     /// Creates a new address specification. This will validate the local part
     /// and domain and perform NFC-normalization.
     pub fn new<LocalPart, Domain>(local_part: LocalPart, domain: Domain) -> Result<Self, ParseError>
@@ -147,7 +397,21 @@ impl AddrSpec {
 
     /// Creates a new address specification with a literal domain. This will
     /// validate the local part and domain and perform NFC-normalization.
-    #[cfg(feature = "literals")]
+    ///
+    /// The domain is the content of the literal without its brackets. Contents
+    /// that look like IP addresses must be valid and are stored in their
+    /// canonical form (see [`DomainLiteral`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::AddrSpec;
+    ///
+    /// let addr_spec = AddrSpec::with_literal("jdoe", "IPv6:2001:DB8:0::1").unwrap();
+    /// assert_eq!(addr_spec.to_string(), "jdoe@[IPv6:2001:db8::1]");
+    ///
+    /// assert!(AddrSpec::with_literal("jdoe", "IPv6:::::").is_err());
+    /// ```
     pub fn with_literal<LocalPart, Domain>(
         local_part: LocalPart,
         domain: Domain,
@@ -159,26 +423,63 @@ impl AddrSpec {
         Self::new_impl(local_part.as_ref(), domain.as_ref(), true)
     }
 
+    /// Creates a new address specification with the given domain literal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    ///
+    /// use addr_spec::AddrSpec;
+    ///
+    /// let addr_spec =
+    ///     AddrSpec::with_domain_literal("jdoe", Ipv4Addr::new(192, 0, 2, 1).into()).unwrap();
+    /// assert_eq!(addr_spec.to_string(), "jdoe@[192.0.2.1]");
+    /// ```
+    #[inline]
+    pub fn with_domain_literal<LocalPart>(
+        local_part: LocalPart,
+        literal: DomainLiteral,
+    ) -> Result<Self, ParseError>
+    where
+        LocalPart: AsRef<str>,
+    {
+        Self::new_impl(local_part.as_ref(), &literal.to_string(), true)
+    }
+
     fn new_impl(local_part: &str, domain: &str, literal: bool) -> Result<Self, ParseError> {
-        if let Some(index) = local_part.find(is_ascii_control_and_not_htab) {
-            return Err(ParseError("invalid character in local part", index));
+        if let Some((index, chr)) = local_part
+            .char_indices()
+            .find(|&(_, chr)| is_ascii_control_and_not_htab(chr))
+        {
+            return Err(ParseError::invalid_character(
+                chr,
+                "invalid character in local part",
+                index,
+                &[],
+            ));
         }
 
-        if literal {
-            if let Some(index) = domain.find(is_not_dtext) {
-                return Err(ParseError("invalid character in literal domain", index));
-            }
+        let domain = if literal {
+            // Domain literals are stored in their canonical form.
+            Cow::Owned(domain.parse::<DomainLiteral>()?.to_string())
         } else {
             // We use the parser here since parsing dot atoms is a pure
             // operation (i.e. independent of any features).
-            let mut parser = Parser::new(domain);
-            parser.parse_dot_atom("empty label in domain")?;
-            parser.check_end("invalid character in domain")?;
-        }
+            let dot_atom = Parser::new(domain).parse_dot_atom("empty label in domain")?;
+            if let Some(chr) = domain[dot_atom.len()..].chars().next() {
+                return Err(ParseError::invalid_character(
+                    chr,
+                    "invalid character in domain",
+                    dot_atom.len(),
+                    &[Token::Atext],
+                ));
+            }
+            Cow::Borrowed(domain)
+        };
         Ok(Self {
             local_part: unicode::normalize(local_part),
             domain: unicode::normalize(domain),
-            #[cfg(feature = "literals")]
             literal,
         })
     }
@@ -218,7 +519,6 @@ impl AddrSpec {
     /// Only use this function if you are sure that the local part and domain
     /// are valid and NFC-normalized. This is typically the case if you are
     /// getting them from a trusted source.
-    #[cfg(feature = "literals")]
     #[inline]
     pub unsafe fn with_literal_unchecked<LocalPart, Domain>(
         local_part: LocalPart,
@@ -231,12 +531,10 @@ impl AddrSpec {
         Self::new_unchecked_impl(local_part.into(), domain.into(), true)
     }
 
-    #[allow(unused_variables)]
     unsafe fn new_unchecked_impl(local_part: String, domain: String, literal: bool) -> Self {
         Self {
             local_part,
             domain,
-            #[cfg(feature = "literals")]
             literal,
         }
     }
@@ -256,18 +554,390 @@ impl AddrSpec {
     /// Returns whether the local part is quoted.
     #[inline]
     pub fn is_quoted(&self) -> bool {
-        self.local_part()
-            .split('.')
-            .any(|s| s.is_empty() || s.contains(is_not_atext))
+        is_quoted(self.local_part())
     }
 
     /// Returns whether the domain is literal.
     #[inline]
     pub fn is_literal(&self) -> bool {
-        #[cfg(feature = "literals")]
-        return self.literal;
-        #[cfg(not(feature = "literals"))]
-        return false;
+        self.literal
+    }
+
+    /// Returns the typed domain literal if the domain is literal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    ///
+    /// use addr_spec::{AddrSpec, DomainLiteral};
+    ///
+    /// let addr_spec = AddrSpec::with_literal("jdoe", "192.0.2.1").unwrap();
+    /// assert_eq!(
+    ///     addr_spec.domain_literal(),
+    ///     Some(DomainLiteral::Ipv4(Ipv4Addr::new(192, 0, 2, 1)))
+    /// );
+    /// ```
+    pub fn domain_literal(&self) -> Option<DomainLiteral> {
+        if !self.literal {
+            return None;
+        }
+        DomainLiteral::classify(&self.domain)
+    }
+
+    /// Returns the special-use class of the domain, or `None` if the domain is
+    /// literal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, DomainClass};
+    ///
+    /// let addr_spec = AddrSpec::new("jdoe", "mail.example.com").unwrap();
+    /// assert_eq!(addr_spec.domain_class(), Some(DomainClass::Example));
+    ///
+    /// let addr_spec = AddrSpec::new("jdoe", "Printer.LOCAL").unwrap();
+    /// assert_eq!(addr_spec.domain_class(), Some(DomainClass::Local));
+    ///
+    /// let addr_spec = AddrSpec::with_literal("jdoe", "127.0.0.1").unwrap();
+    /// assert_eq!(addr_spec.domain_class(), None);
+    /// ```
+    #[inline]
+    pub fn domain_class(&self) -> Option<DomainClass> {
+        if self.literal {
+            return None;
+        }
+        Some(DomainClass::of(&self.domain))
+    }
+
+    /// Returns the class of the IP address if the domain is an IP address
+    /// literal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, IpClass};
+    ///
+    /// let addr_spec = AddrSpec::with_literal("jdoe", "127.0.0.1").unwrap();
+    /// assert_eq!(addr_spec.literal_ip_class(), Some(IpClass::Loopback));
+    ///
+    /// let addr_spec = AddrSpec::new("jdoe", "example.com").unwrap();
+    /// assert_eq!(addr_spec.literal_ip_class(), None);
+    /// ```
+    #[inline]
+    pub fn literal_ip_class(&self) -> Option<IpClass> {
+        self.domain_literal()?.ip_class()
+    }
+
+    /// Returns the domain in its ASCII form, i.e. with U-labels converted into
+    /// A-labels, as needed by DNS and relays without SMTPUTF8 support.
+    ///
+    /// The domain is mapped as defined in [UTS
+    /// #46](https://www.unicode.org/reports/tr46/) and its labels are
+    /// validated as defined in [RFC 5891](https://www.rfc-editor.org/rfc/rfc5891),
+    /// i.e. including the contextual rules, the Bidi rule and disallowed code
+    /// points. The spans of errors are relative to the domain. Literal domains
+    /// are returned as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, ParseErrorKind};
+    ///
+    /// let addr_spec = AddrSpec::new("jdoe", "m\u{fc}nchen.de").unwrap();
+    /// assert_eq!(addr_spec.domain_to_ascii().unwrap(), "xn--mnchen-3ya.de");
+    ///
+    /// let addr_spec = AddrSpec::new("jdoe", "\u{2603}.example").unwrap();
+    /// assert_eq!(
+    ///     addr_spec.domain_to_ascii().unwrap_err().kind(),
+    ///     ParseErrorKind::DisallowedCodePoint { found: '\u{2603}' }
+    /// );
+    /// ```
+    #[cfg(feature = "idna")]
+    pub fn domain_to_ascii(&self) -> Result<Cow<'_, str>, ParseError> {
+        if self.literal {
+            return Ok(Cow::Borrowed(&self.domain));
+        }
+        idn::domain_to_ascii(&self.domain)
+    }
+
+    /// Returns the domain in its Unicode form, i.e. with A-labels converted
+    /// into U-labels, e.g. for display.
+    ///
+    /// The domain is validated like in [`AddrSpec::domain_to_ascii`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::AddrSpec;
+    ///
+    /// let addr_spec = AddrSpec::new("jdoe", "xn--mnchen-3ya.de").unwrap();
+    /// assert_eq!(addr_spec.domain_to_unicode().unwrap(), "m\u{fc}nchen.de");
+    /// ```
+    #[cfg(feature = "idna")]
+    pub fn domain_to_unicode(&self) -> Result<Cow<'_, str>, ParseError> {
+        if self.literal {
+            return Ok(Cow::Borrowed(&self.domain));
+        }
+        idn::domain_to_unicode(&self.domain)
+    }
+
+    /// Returns whether the address can only be transported with the `SMTPUTF8`
+    /// extension of [RFC 6531](https://www.rfc-editor.org/rfc/rfc6531), i.e.
+    /// whether the local part or a domain literal is not ASCII, or the domain
+    /// is not ASCII and cannot be converted into A-labels.
+    ///
+    /// With the `idna` feature, a non-ASCII domain name does not require
+    /// `SMTPUTF8` if it is a valid internationalized domain name (see
+    /// [`AddrSpec::to_ascii`]). Without the feature, no conversion is
+    /// available, so any non-ASCII domain requires `SMTPUTF8`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::AddrSpec;
+    ///
+    /// assert!(!AddrSpec::new("jdoe", "example.com").unwrap().requires_smtputf8());
+    /// assert!(AddrSpec::new("j\u{f6}rg", "example.com").unwrap().requires_smtputf8());
+    /// assert!(AddrSpec::new("jdoe", "\u{2603}.example").unwrap().requires_smtputf8());
+    /// ```
+    pub fn requires_smtputf8(&self) -> bool {
+        if !self.local_part.is_ascii() {
+            return true;
+        }
+        if self.domain.is_ascii() {
+            return false;
+        }
+        #[cfg(feature = "idna")]
+        if !self.literal {
+            return self.domain_to_ascii().is_err();
+        }
+        true
+    }
+
+    /// Returns the address with its domain in ASCII form (see
+    /// [`AddrSpec::domain_to_ascii`]), i.e. downgrades the address for a relay
+    /// without `SMTPUTF8` support as defined in [RFC
+    /// 6857](https://www.rfc-editor.org/rfc/rfc6857#section-3.1).
+    ///
+    /// This fails with [`ParseErrorKind::SmtpUtf8Required`] at the first
+    /// non-ASCII character if the local part or a domain literal is not ASCII (see
+    /// [`AddrSpec::requires_smtputf8`]), or with the respective error if the
+    /// domain is not a valid internationalized domain name. The spans of errors
+    /// are relative to the serialized address.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, ParseErrorKind};
+    ///
+    /// let addr_spec: AddrSpec = "jdoe@m\u{fc}nchen.de".parse().unwrap();
+    /// assert_eq!(addr_spec.to_ascii().unwrap().to_string(), "jdoe@xn--mnchen-3ya.de");
+    ///
+    /// let addr_spec: AddrSpec = "j\u{f6}rg@m\u{fc}nchen.de".parse().unwrap();
+    /// let error = addr_spec.to_ascii().unwrap_err();
+    /// assert_eq!(error.kind(), ParseErrorKind::SmtpUtf8Required);
+    /// assert_eq!(error.span(), 1..3);
+    ///
+    /// let addr_spec: AddrSpec = "jdoe@\u{2603}.example".parse().unwrap();
+    /// let error = addr_spec.to_ascii().unwrap_err();
+    /// assert_eq!(error.kind(), ParseErrorKind::DisallowedCodePoint { found: '\u{2603}' });
+    /// assert_eq!(error.span(), 5..8);
+    /// ```
+    #[cfg(feature = "idna")]
+    #[doc(alias = "downgrade")]
+    pub fn to_ascii(&self) -> Result<Self, ParseError> {
+        let serialized = self.to_string();
+        let domain_start = serialized.len() - self.domain.len() - usize::from(self.literal);
+        // Unlike domain names, domain literals cannot be converted.
+        let end = if self.literal {
+            serialized.len()
+        } else {
+            domain_start
+        };
+        if let Some((index, chr)) = serialized[..end]
+            .char_indices()
+            .find(|(_, chr)| !chr.is_ascii())
+        {
+            return Err(ParseError::new(
+                ParseErrorKind::SmtpUtf8Required,
+                "non-ASCII character requires SMTPUTF8",
+                index..index + chr.len_utf8(),
+                &[],
+            ));
+        }
+        let domain = self
+            .domain_to_ascii()
+            .map_err(|error| error.offset(domain_start))?;
+        Ok(Self {
+            local_part: self.local_part.clone(),
+            domain: domain.into_owned(),
+            literal: self.literal,
+        })
+    }
+
+    /// Returns whether both addresses are the same when comparing their
+    /// domains in ASCII form, e.g. `jdoe@m\u{fc}nchen.de` and
+    /// `jdoe@xn--mnchen-3ya.de`.
+    ///
+    /// Local parts are compared as is. Domains that cannot be converted are
+    /// compared as is as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::AddrSpec;
+    ///
+    /// let unicode: AddrSpec = "jdoe@m\u{fc}nchen.de".parse().unwrap();
+    /// let ascii: AddrSpec = "jdoe@XN--MNCHEN-3YA.DE".parse().unwrap();
+    /// assert_ne!(unicode, ascii);
+    /// assert!(unicode.eq_idna(&ascii));
+    /// ```
+    #[cfg(feature = "idna")]
+    pub fn eq_idna(&self, other: &Self) -> bool {
+        if self.local_part != other.local_part || self.literal != other.literal {
+            return false;
+        }
+        match (self.domain_to_ascii(), other.domain_to_ascii()) {
+            (Ok(domain), Ok(other_domain)) => domain == other_domain,
+            _ => self.domain == other.domain,
+        }
+    }
+
+    /// Returns the confusable skeleton of the address as defined in [UTS
+    /// #39](https://www.unicode.org/reports/tr39/#Confusable_Detection), i.e.
+    /// a form in which visually confusable characters, such as the Latin `a`
+    /// and the Cyrillic `а`, are the same.
+    ///
+    /// The skeleton is only meant for comparison, not for display or
+    /// delivery. Both the local part and the domain are lowercased first, so
+    /// that addresses only differing in case are confusable as well, e.g.
+    /// `Admin@example.com` and `admin@example.com`. With the `idna` feature,
+    /// the A-labels of the domain are converted into U-labels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::AddrSpec;
+    ///
+    /// let latin = AddrSpec::new("paypal", "example.com").unwrap();
+    /// let cyrillic = AddrSpec::new("p\u{430}ypal", "example.com").unwrap();
+    /// assert_ne!(latin, cyrillic);
+    /// assert_eq!(latin.skeleton(), cyrillic.skeleton());
+    /// ```
+    #[cfg(feature = "security")]
+    pub fn skeleton(&self) -> String {
+        let local_part = unicode::skeleton(&self.local_part.to_lowercase());
+        if self.literal {
+            return [&local_part, "@[", &self.domain, "]"].concat();
+        }
+        let domain = unicode::skeleton(&unicode::unicode_domain(&self.domain));
+        [local_part, domain].join("@")
+    }
+
+    /// Returns whether the addresses are visually confusable, i.e. whether
+    /// their skeletons are the same (see [`AddrSpec::skeleton`]).
+    ///
+    /// Note that equal addresses are confusable as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::AddrSpec;
+    ///
+    /// let admin = AddrSpec::new("admin", "example.com").unwrap();
+    /// assert!(admin.is_confusable(&AddrSpec::new("\u{430}dmin", "example.com").unwrap()));
+    /// assert!(admin.is_confusable(&AddrSpec::new("admin", "ex\u{430}mple.COM").unwrap()));
+    /// assert!(!admin.is_confusable(&AddrSpec::new("admins", "example.com").unwrap()));
+    /// ```
+    #[cfg(feature = "security")]
+    pub fn is_confusable(&self, other: &Self) -> bool {
+        self.skeleton() == other.skeleton()
+    }
+
+    /// Returns the restriction level of the local part (see
+    /// [`RestrictionLevel`]). ASCII punctuation, such as the `+` of
+    /// subaddresses, is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, RestrictionLevel};
+    ///
+    /// let addr_spec = AddrSpec::new("p\u{430}ypal", "example.com").unwrap();
+    /// assert_eq!(
+    ///     addr_spec.local_part_restriction_level(),
+    ///     RestrictionLevel::MinimallyRestrictive
+    /// );
+    /// ```
+    #[cfg(feature = "security")]
+    #[inline]
+    pub fn local_part_restriction_level(&self) -> RestrictionLevel {
+        RestrictionLevel::of_local_part(&self.local_part)
+    }
+
+    /// Returns the restriction levels of the labels of the domain, in order
+    /// (see [`RestrictionLevel`]). With the `idna` feature, A-labels are
+    /// decoded first. Literal domains have no labels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, RestrictionLevel};
+    ///
+    /// let addr_spec = AddrSpec::new("jdoe", "\u{43f}\u{440}\u{438}\u{43c}\u{435}\u{440}.com").unwrap();
+    /// assert_eq!(
+    ///     addr_spec.domain_restriction_levels(),
+    ///     [RestrictionLevel::SingleScript, RestrictionLevel::AsciiOnly]
+    /// );
+    /// ```
+    #[cfg(feature = "security")]
+    pub fn domain_restriction_levels(&self) -> Vec<RestrictionLevel> {
+        if self.literal {
+            return Vec::new();
+        }
+        self.domain
+            .split('.')
+            .map(RestrictionLevel::of_label)
+            .collect()
+    }
+
+    /// Returns the least restrictive level of the local part and the labels
+    /// of the domain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{AddrSpec, RestrictionLevel};
+    ///
+    /// let addr_spec = AddrSpec::new("j\u{f6}rg", "example.com").unwrap();
+    /// assert_eq!(addr_spec.restriction_level(), RestrictionLevel::SingleScript);
+    /// ```
+    #[cfg(feature = "security")]
+    pub fn restriction_level(&self) -> RestrictionLevel {
+        self.domain_restriction_levels()
+            .into_iter()
+            .fold(self.local_part_restriction_level(), Ord::max)
+    }
+
+    /// Returns whether the address contains default-ignorable code points,
+    /// i.e. characters that are usually invisible such as ZWJ, ZWNJ, the soft
+    /// hyphen or bidi controls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::AddrSpec;
+    ///
+    /// assert!(!AddrSpec::new("jdoe", "example.com").unwrap().has_default_ignorables());
+    /// assert!(AddrSpec::new("j\u{200d}doe", "example.com").unwrap().has_default_ignorables());
+    /// assert!(AddrSpec::new("jdoe", "exam\u{ad}ple.com").unwrap().has_default_ignorables());
+    /// ```
+    pub fn has_default_ignorables(&self) -> bool {
+        self.local_part
+            .chars()
+            .chain(self.domain.chars())
+            .any(unicode::is_default_ignorable)
     }
 
     /// Returns the local part and domain of the address.
@@ -282,8 +952,6 @@ impl AddrSpec {
     /// line-based protocols such as SMTP and need to ensure that the local part
     /// and domain fit on a single line or require folding white-spaces.
     pub fn into_serialized_parts(self) -> (String, String) {
-        // Note literals will be optimized away by the compiler if the feature
-        // is disabled.
         match (self.is_quoted(), self.is_literal()) {
             (false, false) => (self.local_part, self.domain),
             (true, false) => (
@@ -300,32 +968,14 @@ impl AddrSpec {
 }
 
 impl fmt::Display for AddrSpec {
+    #[inline]
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if !self.is_quoted() {
-            formatter.write_str(self.local_part())?;
-        } else {
-            formatter.write_char('"')?;
-            for chr in quote(self.local_part()).chars() {
-                formatter.write_char(chr)?;
-            }
-            formatter.write_char('"')?;
-        }
-
-        formatter.write_char('@')?;
-
-        // Note literals will be optimized away by the compiler if the feature
-        // is disabled.
-        if !self.is_literal() {
-            formatter.write_str(self.domain())?;
-        } else {
-            formatter.write_char('[')?;
-            for chr in self.domain().chars() {
-                formatter.write_char(chr)?;
-            }
-            formatter.write_char(']')?;
-        }
-
-        Ok(())
+        write_addr_spec(
+            formatter,
+            self.local_part(),
+            self.domain(),
+            self.is_literal(),
+        )
     }
 }
 
@@ -524,6 +1174,16 @@ mod tests {
         assert_eq!(addr_spec.to_string(), "jdoe@machine.example");
     }
 
+    #[test]
+    fn test_addr_spec_from_str_with_semicolon() {
+        assert!(AddrSpec::from_str("jd;oe@machine.example").is_err());
+
+        let addr_spec = AddrSpec::from_str("\"jd;oe\"@machine.example").unwrap();
+        assert_eq!(addr_spec.local_part(), "jd;oe");
+        assert_eq!(addr_spec.domain(), "machine.example");
+        assert_eq!(addr_spec.to_string(), "\"jd;oe\"@machine.example");
+    }
+
     #[test]
     fn test_addr_spec_from_str_with_escape_and_quote() {
         let addr_spec = AddrSpec::from_str("\"jdoe\\\"\"@machine.example").unwrap();
@@ -545,7 +1205,12 @@ mod tests {
     fn test_addr_spec_from_str_with_white_spaces_and_white_space_escape_and_quote() {
         assert_eq!(
             AddrSpec::from_str("\"jdoe \\  \"@machine.example").unwrap_err(),
-            ParseError("invalid character in quoted local part", 5)
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: ' ' },
+                "invalid character in quoted local part",
+                5..6,
+                &[Token::Qtext, Token::Char('"')]
+            )
         );
     }
 
@@ -608,6 +1273,53 @@ mod tests {
         assert_eq!(addr_spec.domain(), "😄😄😄");
         assert_eq!(addr_spec.to_string(), "😄😄😄@[😄😄😄]");
     }
+
+    #[test]
+    fn test_addr_spec_requires_smtputf8_with_unicode_domain_literal() {
+        let addr_spec = AddrSpec::with_literal("jdoe", "\u{fc}ber").unwrap();
+        assert!(addr_spec.requires_smtputf8());
+        let addr_spec = AddrSpec::with_literal("jdoe", "192.0.2.1").unwrap();
+        assert!(!addr_spec.requires_smtputf8());
+    }
+
+    #[test]
+    fn test_addr_spec_requires_smtputf8_with_unicode_domain() {
+        let addr_spec = AddrSpec::new("jdoe", "m\u{fc}nchen.de").unwrap();
+        assert_eq!(addr_spec.requires_smtputf8(), !cfg!(feature = "idna"));
+        let addr_spec = AddrSpec::new("jdoe", "\u{2603}.example").unwrap();
+        assert!(addr_spec.requires_smtputf8());
+    }
+
+    #[test]
+    #[cfg(feature = "idna")]
+    fn test_addr_spec_to_ascii_with_unicode_domain_literal() {
+        let addr_spec = AddrSpec::with_literal("jdoe", "\u{fc}ber").unwrap();
+        let error = addr_spec.to_ascii().unwrap_err();
+        assert_eq!(error.kind(), ParseErrorKind::SmtpUtf8Required);
+        assert_eq!(error.span(), 6..8);
+        let addr_spec = AddrSpec::with_literal("jdoe", "192.0.2.1").unwrap();
+        assert_eq!(addr_spec.to_ascii().unwrap(), addr_spec);
+    }
+
+    #[test]
+    #[cfg(feature = "security")]
+    fn test_addr_spec_skeleton() {
+        let addr_spec = AddrSpec::from_str("p\u{430}yp\u{430}l@EX\u{430}MPLE.com").unwrap();
+        // The skeleton of `m` is `rn`.
+        assert_eq!(addr_spec.skeleton(), "paypal@exarnple.corn");
+        let addr_spec = AddrSpec::from_str("rn@example.com").unwrap();
+        assert!(addr_spec.is_confusable(&AddrSpec::from_str("m@example.com").unwrap()));
+        let addr_spec = AddrSpec::from_str("Admin@example.com").unwrap();
+        assert!(addr_spec.is_confusable(&AddrSpec::from_str("admin@example.com").unwrap()));
+        assert!(addr_spec.is_confusable(&AddrSpec::from_str("\u{430}DMIN@example.com").unwrap()));
+    }
+
+    #[test]
+    #[cfg(all(feature = "security", feature = "idna"))]
+    fn test_addr_spec_skeleton_with_a_labels() {
+        let addr_spec = AddrSpec::from_str("jdoe@xn--pypal-4ve.com").unwrap();
+        assert_eq!(addr_spec.skeleton(), "jdoe@paypal.corn");
+    }
 }
 
 #[cfg(all(test, feature = "nightly"))]
@@ -662,6 +1374,28 @@ mod benches {
         }
     }
 
+    mod addr_spec_ref {
+        use super::*;
+
+        #[bench]
+        fn bench_trivial(b: &mut test::Bencher) {
+            b.iter(|| {
+                let address = AddrSpecRef::parse("test@example.com").unwrap();
+                assert_eq!(address.local_part(), "test");
+                assert_eq!(address.domain(), "example.com");
+            });
+        }
+
+        #[bench]
+        fn bench_quoted_local_part(b: &mut test::Bencher) {
+            b.iter(|| {
+                let address = AddrSpecRef::parse("\"test\"@example.com").unwrap();
+                assert_eq!(address.local_part(), "test");
+                assert_eq!(address.domain(), "example.com");
+            });
+        }
+    }
+
     #[cfg(feature = "email_address")]
     mod email_address {
         use super::*;
@@ -0,0 +1,397 @@
+use std::{
+    fmt::{self, Write},
+    str::FromStr,
+};
+
+use super::{
+    ascii,
+    parser::{is_ascii_control_and_not_htab, is_not_phrase_atext, Parser},
+    unicode, AddrSpec, ParseError, ParserOptions,
+};
+
+fn quote(value: &str) -> String {
+    ascii::escape!(value, b'\\', b'"')
+}
+
+/// Mailbox as defined in [RFC
+/// 5322](https://tools.ietf.org/html/rfc5322#section-3.4), i.e. an address
+/// specification with an optional display name.
+///
+/// The display name is decoded, i.e. quoted strings are unquoted and the words
+/// of the phrase are joined with a single space. Like the address
+/// specification, it is normalized using the
+/// [NFC](https://unicode.org/reports/tr15/#Norm_Forms).
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+///
+/// use addr_spec::Mailbox;
+///
+/// let mailbox = Mailbox::from_str("John Doe <jdoe@example.com>").unwrap();
+/// assert_eq!(mailbox.display_name(), Some("John Doe"));
+/// assert_eq!(mailbox.addr_spec().to_string(), "jdoe@example.com");
+/// assert_eq!(mailbox.to_string(), "John Doe <jdoe@example.com>");
+/// ```
+///
+/// Display names are only quoted if they need to be:
+///
+/// ```
+/// use std::str::FromStr;
+///
+/// use addr_spec::Mailbox;
+///
+/// let mailbox = Mailbox::from_str(r#""Doe, John" <jdoe@example.com>"#).unwrap();
+/// assert_eq!(mailbox.display_name(), Some("Doe, John"));
+/// assert_eq!(mailbox.to_string(), r#""Doe, John" <jdoe@example.com>"#);
+///
+/// let mailbox = Mailbox::from_str(r#""John Doe" <jdoe@example.com>"#).unwrap();
+/// assert_eq!(mailbox.display_name(), Some("John Doe"));
+/// assert_eq!(mailbox.to_string(), "John Doe <jdoe@example.com>");
+/// ```
+///
+/// A bare address specification is a mailbox as well:
+///
+/// ```
+/// use std::str::FromStr;
+///
+/// use addr_spec::Mailbox;
+///
+/// let mailbox = Mailbox::from_str("jdoe@example.com").unwrap();
+/// assert_eq!(mailbox.display_name(), None);
+/// assert_eq!(mailbox.to_string(), "jdoe@example.com");
+/// ```
+///
+/// # References
+///
+/// - [RFC 5322](https://tools.ietf.org/html/rfc5322#section-3.4)
+/// - [RFC 6532](https://tools.ietf.org/html/rfc6532)
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Mailbox {
+    display_name: Option<String>,
+    addr_spec: AddrSpec,
+}
+
+impl Mailbox {
+    /// Creates a new mailbox with a display name. This will validate the
+    /// display name and perform NFC-normalization.
+    ///
+    /// Use [`From<AddrSpec>`](#impl-From<AddrSpec>-for-Mailbox) to create a
+    /// mailbox without a display name.
+    pub fn new<DisplayName>(
+        display_name: DisplayName,
+        addr_spec: AddrSpec,
+    ) -> Result<Self, ParseError>
+    where
+        DisplayName: AsRef<str>,
+    {
+        let display_name = display_name.as_ref();
+        if let Some((index, chr)) = display_name
+            .char_indices()
+            .find(|&(_, chr)| is_ascii_control_and_not_htab(chr))
+        {
+            return Err(ParseError::invalid_character(
+                chr,
+                "invalid character in display name",
+                index,
+                &[],
+            ));
+        }
+        Ok(Self {
+            display_name: Some(unicode::normalize(display_name)),
+            addr_spec,
+        })
+    }
+
+    /// Parses a mailbox using the given options instead of the defaults
+    /// determined by the enabled features.
+    ///
+    /// The options apply to the address specification. White spaces are
+    /// always allowed in the display name and around the angle brackets.
+    #[inline]
+    pub fn parse_with(mailbox: &str, options: &ParserOptions) -> Result<Self, ParseError> {
+        Parser::with_options(mailbox, options).parse_mailbox()
+    }
+
+    #[inline]
+    pub(crate) fn from_parts(display_name: Option<String>, addr_spec: AddrSpec) -> Self {
+        Self {
+            display_name,
+            addr_spec,
+        }
+    }
+
+    /// Returns the display name of the mailbox, if any.
+    #[inline]
+    pub fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+
+    /// Returns the address specification of the mailbox.
+    #[inline]
+    pub fn addr_spec(&self) -> &AddrSpec {
+        &self.addr_spec
+    }
+
+    /// Returns the display name and address specification of the mailbox.
+    #[inline]
+    pub fn into_parts(self) -> (Option<String>, AddrSpec) {
+        (self.display_name, self.addr_spec)
+    }
+}
+
+impl From<AddrSpec> for Mailbox {
+    #[inline]
+    fn from(addr_spec: AddrSpec) -> Self {
+        Self {
+            display_name: None,
+            addr_spec,
+        }
+    }
+}
+
+impl fmt::Display for Mailbox {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(display_name) = self.display_name() else {
+            return self.addr_spec.fmt(formatter);
+        };
+
+        write_display_name(formatter, display_name)?;
+        write!(formatter, " <{}>", self.addr_spec)
+    }
+}
+
+/// Writes a display name, quoting it only if necessary.
+pub(crate) fn write_display_name(
+    formatter: &mut fmt::Formatter<'_>,
+    display_name: &str,
+) -> fmt::Result {
+    // The display name can be written as a sequence of atoms if none of the
+    // words are empty or contain special characters.
+    if display_name
+        .split(' ')
+        .any(|word| word.is_empty() || word.contains(is_not_phrase_atext))
+    {
+        formatter.write_char('"')?;
+        formatter.write_str(&quote(display_name))?;
+        formatter.write_char('"')
+    } else {
+        formatter.write_str(display_name)
+    }
+}
+
+impl FromStr for Mailbox {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(mailbox: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(mailbox, &ParserOptions::new())
+    }
+}
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "serde")]
+impl Serialize for Mailbox {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Mailbox {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParseErrorKind, Token};
+
+    #[test]
+    fn test_mailbox_from_str() {
+        let mailbox = Mailbox::from_str("John Doe <jdoe@machine.example>").unwrap();
+        assert_eq!(mailbox.display_name(), Some("John Doe"));
+        assert_eq!(mailbox.addr_spec().local_part(), "jdoe");
+        assert_eq!(mailbox.addr_spec().domain(), "machine.example");
+        assert_eq!(mailbox.to_string(), "John Doe <jdoe@machine.example>");
+    }
+
+    #[test]
+    fn test_mailbox_from_str_without_display_name() {
+        let mailbox = Mailbox::from_str("jdoe@machine.example").unwrap();
+        assert_eq!(mailbox.display_name(), None);
+        assert_eq!(mailbox.addr_spec().local_part(), "jdoe");
+        assert_eq!(mailbox.addr_spec().domain(), "machine.example");
+        assert_eq!(mailbox.to_string(), "jdoe@machine.example");
+    }
+
+    #[test]
+    fn test_mailbox_from_str_with_angle_address_only() {
+        let mailbox = Mailbox::from_str("<jdoe@machine.example>").unwrap();
+        assert_eq!(mailbox.display_name(), None);
+        assert_eq!(mailbox.to_string(), "jdoe@machine.example");
+    }
+
+    #[test]
+    fn test_mailbox_from_str_with_quoted_local_part() {
+        let mailbox = Mailbox::from_str("\"jdoe.\"@machine.example").unwrap();
+        assert_eq!(mailbox.display_name(), None);
+        assert_eq!(mailbox.addr_spec().local_part(), "jdoe.");
+    }
+
+    #[test]
+    fn test_mailbox_from_str_with_quoted_display_name() {
+        let mailbox = Mailbox::from_str("\"Doe, John\" <jdoe@machine.example>").unwrap();
+        assert_eq!(mailbox.display_name(), Some("Doe, John"));
+        assert_eq!(mailbox.to_string(), "\"Doe, John\" <jdoe@machine.example>");
+    }
+
+    #[test]
+    fn test_mailbox_from_str_with_unnecessarily_quoted_display_name() {
+        let mailbox = Mailbox::from_str("\"John\" Doe<jdoe@machine.example>").unwrap();
+        assert_eq!(mailbox.display_name(), Some("John Doe"));
+        assert_eq!(mailbox.to_string(), "John Doe <jdoe@machine.example>");
+    }
+
+    #[test]
+    fn test_mailbox_from_str_with_escape_in_display_name() {
+        let mailbox = Mailbox::from_str("\"John \\\"Jr\\\" Doe\" <jdoe@machine.example>").unwrap();
+        assert_eq!(mailbox.display_name(), Some("John \"Jr\" Doe"));
+        assert_eq!(
+            mailbox.to_string(),
+            "\"John \\\"Jr\\\" Doe\" <jdoe@machine.example>"
+        );
+    }
+
+    #[test]
+    fn test_mailbox_from_str_with_folded_display_name() {
+        let mailbox = Mailbox::from_str("\"John\r\n Doe\"\r\n <jdoe@machine.example>").unwrap();
+        assert_eq!(mailbox.display_name(), Some("John Doe"));
+    }
+
+    #[test]
+    fn test_mailbox_from_str_with_empty_quoted_display_name() {
+        let mailbox = Mailbox::from_str("\"\" <jdoe@machine.example>").unwrap();
+        assert_eq!(mailbox.display_name(), Some(""));
+        assert_eq!(mailbox.to_string(), "\"\" <jdoe@machine.example>");
+    }
+
+    #[test]
+    fn test_mailbox_from_str_with_unicode() {
+        let mailbox = Mailbox::from_str("😄 😄 <😄😄😄@😄😄😄>").unwrap();
+        assert_eq!(mailbox.display_name(), Some("😄 😄"));
+        assert_eq!(mailbox.to_string(), "😄 😄 <😄😄😄@😄😄😄>");
+    }
+
+    #[test]
+    fn test_mailbox_from_str_without_closing_angle_bracket() {
+        assert_eq!(
+            Mailbox::from_str("John Doe <jdoe@machine.example").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::UnterminatedAngleAddress,
+                "expected '>' for angle address",
+                30..30,
+                &[Token::Char('>')]
+            )
+        );
+    }
+
+    #[test]
+    fn test_mailbox_from_str_with_unterminated_quoted_display_name() {
+        assert_eq!(
+            Mailbox::from_str("\"John Doe <jdoe@machine.example>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::UnterminatedQuote,
+                "expected '\"' for quoted display name",
+                32..32,
+                &[Token::Char('"')]
+            )
+        );
+    }
+
+    #[test]
+    fn test_mailbox_from_str_with_trailing_input() {
+        assert_eq!(
+            Mailbox::from_str("John Doe <jdoe@machine.example> x").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::TrailingInput,
+                "expected end of mailbox",
+                32..33,
+                &[Token::End]
+            )
+        );
+    }
+
+    #[cfg(not(feature = "white-spaces"))]
+    #[test]
+    fn test_mailbox_from_str_without_angle_address() {
+        assert_eq!(
+            Mailbox::from_str("John Doe jdoe@machine.example").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: ' ' },
+                "expected '@'",
+                4..5,
+                &[Token::Atext, Token::Char('.'), Token::Char('@')]
+            )
+        );
+    }
+
+    #[cfg(feature = "comments")]
+    #[test]
+    fn test_mailbox_from_str_with_comments() {
+        let mailbox =
+            Mailbox::from_str("(Sir) John (The Adventurer) Doe <jdoe@machine.example> (Home)")
+                .unwrap();
+        assert_eq!(mailbox.display_name(), Some("John Doe"));
+        assert_eq!(mailbox.to_string(), "John Doe <jdoe@machine.example>");
+    }
+
+    #[test]
+    fn test_mailbox_new() {
+        let addr_spec = AddrSpec::new("jdoe", "machine.example").unwrap();
+        let mailbox = Mailbox::new("Doe, John", addr_spec.clone()).unwrap();
+        assert_eq!(mailbox.display_name(), Some("Doe, John"));
+        assert_eq!(mailbox.addr_spec(), &addr_spec);
+        assert_eq!(mailbox.to_string(), "\"Doe, John\" <jdoe@machine.example>");
+    }
+
+    #[test]
+    fn test_mailbox_new_with_invalid_display_name() {
+        let addr_spec = AddrSpec::new("jdoe", "machine.example").unwrap();
+        assert_eq!(
+            Mailbox::new("John\r\nDoe", addr_spec).unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: '\r' },
+                "invalid character in display name",
+                4..5,
+                &[]
+            )
+        );
+    }
+
+    #[test]
+    fn test_mailbox_display_name_with_dot_is_quoted() {
+        let addr_spec = AddrSpec::new("jdoe", "machine.example").unwrap();
+        let mailbox = Mailbox::new("John Q. Doe", addr_spec).unwrap();
+        assert_eq!(
+            mailbox.to_string(),
+            "\"John Q. Doe\" <jdoe@machine.example>"
+        );
+        assert_eq!(Mailbox::from_str(&mailbox.to_string()).unwrap(), mailbox);
+    }
+}
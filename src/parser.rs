@@ -1,7 +1,20 @@
-use std::{error::Error, fmt, mem::ManuallyDrop, str::Chars};
+use std::{borrow::Cow, mem::ManuallyDrop, ops::Range, str::Chars};
 
+mod html5;
+mod smtp;
+
+#[cfg(feature = "idna")]
+use super::idn;
+use super::prefix;
 use super::unicode;
-use super::AddrSpec;
+#[cfg(feature = "precis")]
+use super::PrecisProfile;
+#[cfg(feature = "security")]
+use super::RestrictionLevel;
+use super::{
+    quote, AddrSpec, AddrSpecRef, Address, Charset, Diagnostic, DiagnosticKind, DomainLiteral,
+    Group, Mailbox, ParseError, ParseErrorKind, PrefixCheck, Profile, SyntaxTree, Token,
+};
 
 pub const fn is_ascii_control_and_not_htab(chr: char) -> bool {
     chr.is_ascii_control() && chr != '\t'
@@ -15,103 +28,965 @@ pub const fn is_not_atext(chr: char) -> bool {
     is_ascii_control_or_space(chr)
         || matches!(
             chr,
-            '"' | '(' | ')' | ',' | ':' | '<' | '>' | '@' | '[' | ']' | '\\'
+            '"' | '(' | ')' | ',' | ':' | ';' | '<' | '>' | '@' | '[' | ']' | '\\'
         )
 }
 
+pub const fn is_not_phrase_atext(chr: char) -> bool {
+    is_not_atext(chr) || chr == '.'
+}
+
 pub const fn is_not_dtext(chr: char) -> bool {
     is_ascii_control_or_space(chr) || matches!(chr, '[' | ']' | '\\')
 }
 
-/// A error that can occur when parsing or creating an address specification.
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-pub struct ParseError(pub(super) &'static str, pub(super) usize);
+/// Options controlling which parts of the address grammar are accepted.
+///
+/// The default options are determined by the enabled Cargo features, so
+/// `ParserOptions::default()` parses exactly like [`AddrSpec::from_str`]. Each
+/// option can be switched at runtime regardless of the enabled features, except
+/// that [`ParserOptions::normalization`] has no effect without the
+/// `normalization` feature.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{AddrSpec, ParserOptions};
+///
+/// let mut options = ParserOptions::default();
+/// options.white_spaces = true;
+/// options.comments = true;
+///
+/// let addr_spec = AddrSpec::parse_with("jdoe@(John Doe) machine.example", &options).unwrap();
+/// assert_eq!(addr_spec.to_string(), "jdoe@machine.example");
+/// ```
+///
+/// [`AddrSpec::from_str`]: std::str::FromStr::from_str
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub struct ParserOptions {
+    /// Whether folding white spaces are allowed around the local part and
+    /// domain as well as inside quoted strings, comments and domain literals.
+    ///
+    /// Defaults to `true` if the `white-spaces` feature is enabled.
+    pub white_spaces: bool,
+    /// Whether comments are allowed around the local part and domain.
+    ///
+    /// Comments may only contain white spaces if `white_spaces` is enabled as
+    /// well. Defaults to `true` if the `comments` feature is enabled.
+    pub comments: bool,
+    /// Whether literal domains are allowed.
+    ///
+    /// Defaults to `true` if the `literals` feature is enabled.
+    pub literals: bool,
+    /// Whether the obsolete syntax of [Section 4,
+    /// RFC 5322](https://www.rfc-editor.org/rfc/rfc5322#section-4) is
+    /// allowed, e.g. `john . doe@example . com`, `"john".doe@example.com` or
+    /// display names containing periods.
+    ///
+    /// Obsolete forms are normalized into the address specification the
+    /// modern grammar would give. Defaults to `false`.
+    pub obsolete: bool,
+    /// Whether the local part and domain are NFC-normalized.
+    ///
+    /// Defaults to `true` if the `normalization` feature is enabled. Without
+    /// the feature, the normalization tables are not available and enabling
+    /// this option has no effect.
+    pub normalization: bool,
+    /// The PRECIS profile enforced on the local part instead of plain NFC
+    /// normalization (see [`PrecisProfile`](crate::PrecisProfile)), e.g.
+    /// `Some(PrecisProfile::UsernameCaseMapped)` maps `\u{ff2a}Doe` to
+    /// `jdoe`. The domain is not affected. Defaults to `None`.
+    #[cfg(feature = "precis")]
+    pub precis: Option<PrecisProfile>,
+    /// The legacy charset used to decode byte input that is not valid UTF-8,
+    /// e.g. when using [`AddrSpec::parse_bytes_with`].
+    ///
+    /// Valid UTF-8 input is always decoded as UTF-8. Defaults to `None`, i.e.
+    /// invalid UTF-8 is an error.
+    pub fallback_charset: Option<Charset>,
+    /// The grammar used to parse address specifications.
+    ///
+    /// Defaults to [`Profile::Rfc5322`].
+    pub profile: Profile,
+    /// Whether the length limits of [Section 4.5.3.1, RFC
+    /// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.5.3.1) are
+    /// enforced, i.e. 64 octets for the local part, 255 octets for the
+    /// domain, 63 octets for each label and 254 octets for the whole address
+    /// (256 octets for the path including its angle brackets).
+    ///
+    /// Lengths are counted in octets of the UTF-8 encoded, serialized
+    /// address. Defaults to `false`, except for [`Profile::Rfc5321`] when
+    /// using [`ParserOptions::from_profile`].
+    pub length_limits: bool,
+    /// Whether the domain must be a valid hostname as defined in [Section
+    /// 2.1, RFC 1123](https://www.rfc-editor.org/rfc/rfc1123#section-2.1),
+    /// i.e. consist of labels of letters, digits and hyphens without leading
+    /// or trailing hyphens, each at most 63 octets long, and have a
+    /// top-level domain that is not all-numeric.
+    ///
+    /// With the `idna` feature, non-ASCII labels (U-labels) must be valid
+    /// IDNA2008 labels, where only their case is mapped, and are checked in
+    /// their A-label form. Otherwise, they may contain any non-ASCII
+    /// characters and are measured by their UTF-8 length. Domain literals are
+    /// not affected. Defaults to `false`.
+    pub hostname: bool,
+    /// Whether the domain must consist of at least two labels, e.g.
+    /// `example.com` rather than `localhost`.
+    ///
+    /// Domain literals are not affected. Defaults to `false`.
+    pub multiple_labels: bool,
+    /// Whether IP address literals must be globally reachable, i.e. whether
+    /// literals such as `[127.0.0.1]` or `[IPv6:fe80::1]` are rejected (see
+    /// [`IpClass`](crate::IpClass)).
+    ///
+    /// Other domain literals are not affected. Defaults to `false`.
+    pub global_literals: bool,
+    /// Whether default-ignorable code points, i.e. usually invisible
+    /// characters such as ZWJ, ZWNJ, the soft hyphen or bidi controls, are
+    /// allowed. Defaults to `true`.
+    pub default_ignorables: bool,
+    /// The restriction level that the local part and each label of the domain
+    /// must satisfy (see [`RestrictionLevel`](crate::RestrictionLevel)), e.g.
+    /// `Some(RestrictionLevel::HighlyRestrictive)` rejects `p\u{430}ypal` with
+    /// a Cyrillic `а`. Defaults to `None`.
+    #[cfg(feature = "security")]
+    pub restriction_level: Option<RestrictionLevel>,
+}
 
-impl ParseError {
-    /// Returns a static error message.
-    #[inline]
-    pub fn message(&self) -> &'static str {
-        self.0
+impl ParserOptions {
+    /// Creates the default options as determined by the enabled features.
+    pub const fn new() -> Self {
+        Self {
+            white_spaces: cfg!(feature = "white-spaces"),
+            comments: cfg!(feature = "comments"),
+            literals: cfg!(feature = "literals"),
+            obsolete: false,
+            normalization: cfg!(feature = "normalization"),
+            #[cfg(feature = "precis")]
+            precis: None,
+            fallback_charset: None,
+            profile: Profile::Rfc5322,
+            length_limits: false,
+            hostname: false,
+            multiple_labels: false,
+            global_literals: false,
+            default_ignorables: true,
+            #[cfg(feature = "security")]
+            restriction_level: None,
+        }
     }
 
-    /// Returns the byte index where the error occurred.
-    #[inline]
-    pub fn index(&self) -> usize {
-        self.1
+    /// Creates options that accept exactly the addresses of the given
+    /// profile.
+    ///
+    /// For [`Profile::Rfc5322`], white spaces, comments and literals are
+    /// allowed, i.e. the full modern grammar of RFC 5322 is accepted.
+    pub const fn from_profile(profile: Profile) -> Self {
+        let rfc5322 = matches!(profile, Profile::Rfc5322);
+        Self {
+            white_spaces: rfc5322,
+            comments: rfc5322,
+            literals: rfc5322,
+            length_limits: matches!(profile, Profile::Rfc5321),
+            profile,
+            ..Self::new()
+        }
     }
 }
 
-impl fmt::Display for ParseError {
-    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            formatter,
-            "parse error at index {}: {}",
-            self.message(),
-            self.index()
-        )
+impl From<Profile> for ParserOptions {
+    #[inline]
+    fn from(profile: Profile) -> Self {
+        Self::from_profile(profile)
     }
 }
 
-impl Error for ParseError {}
+impl Default for ParserOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 pub struct Parser<'a> {
     input: &'a str,
     iterator: Chars<'a>,
+    options: ParserOptions,
+    diagnostics: Option<Vec<Diagnostic>>,
 }
 
 impl<'a> Parser<'a> {
     #[inline]
     pub fn new(input: &'a str) -> Parser<'a> {
+        Self::with_options(input, &ParserOptions::new())
+    }
+
+    #[inline]
+    pub fn with_options(input: &'a str, options: &ParserOptions) -> Parser<'a> {
         Parser {
             input,
             iterator: input.chars(),
+            options: *options,
+            diagnostics: None,
         }
     }
 
     pub fn parse(mut self) -> Result<AddrSpec, ParseError> {
-        #[cfg(feature = "white-spaces")]
-        self.parse_cfws()?;
-        let local_part = self.parse_local_part()?;
-        #[cfg(feature = "white-spaces")]
-        self.parse_cfws()?;
-        self.skip_at()?;
-        #[cfg(feature = "white-spaces")]
-        self.parse_cfws()?;
-        // `literal` only used when feature is enabled
-        #[allow(unused_variables)]
-        let (domain, literal) = self.parse_domain()?;
-        #[cfg(feature = "white-spaces")]
-        self.parse_cfws()?;
+        let addr_spec = self.parse_addr_spec()?;
+        self.check_end("expected end of address")?;
+        Ok(addr_spec)
+    }
+
+    /// Parses an address specification and collects diagnostics about valid
+    /// but unusual forms, ordered by their position.
+    pub fn parse_with_diagnostics(mut self) -> Result<(AddrSpec, Vec<Diagnostic>), ParseError> {
+        self.diagnostics = Some(Vec::new());
+        let addr_spec = self.parse_addr_spec()?;
+        let mut diagnostics = self.diagnostics.take().unwrap_or_default();
+        self.check_end("expected end of address")?;
+        diagnostics.sort_by_key(|diagnostic| diagnostic.span().start);
+        Ok((addr_spec, diagnostics))
+    }
+
+    pub fn parse_mailbox(mut self) -> Result<Mailbox, ParseError> {
+        let mailbox = self.parse_mailbox_impl()?;
+        self.check_end("expected end of mailbox")?;
+        Ok(mailbox)
+    }
+
+    /// Parses an address specification into a lossless syntax tree.
+    pub fn parse_syntax_tree(mut self) -> Result<SyntaxTree<'a>, ParseError> {
+        let (addr_spec, local_part, domain) = self.parse_addr_spec_with_spans()?;
+        let input = self.input;
         self.check_end("expected end of address")?;
-        Ok(AddrSpec {
+        Ok(SyntaxTree::new(
+            input,
+            addr_spec.into_owned(),
             local_part,
             domain,
-            #[cfg(feature = "literals")]
-            literal,
+        ))
+    }
+
+    /// Parses an address specification that borrows from the input whenever
+    /// possible.
+    pub fn parse_ref(mut self) -> Result<AddrSpecRef<'a>, ParseError> {
+        let (addr_spec, _, _) = self.parse_addr_spec_with_spans()?;
+        self.check_end("expected end of address")?;
+        Ok(addr_spec)
+    }
+
+    /// Parses an address specification that may be cut off at the end of the
+    /// input.
+    pub fn check_prefix(self) -> PrefixCheck {
+        let input = self.input;
+        let options = self.options;
+        match self.parse() {
+            Ok(addr_spec) => PrefixCheck::Complete(addr_spec),
+            Err(error) if prefix::is_truncated(&error, input, &options) => {
+                PrefixCheck::IncompleteButViable
+            }
+            // A trailing carriage return may start a folding white space.
+            Err(error) if input.ends_with('\r') && error.index() == input.len() - 1 => {
+                match Parser::with_options(&format!("{input}\n"), &options).check_prefix() {
+                    PrefixCheck::Invalid(_) => PrefixCheck::Invalid(error),
+                    _ => PrefixCheck::IncompleteButViable,
+                }
+            }
+            Err(error) => PrefixCheck::Invalid(error),
+        }
+    }
+
+    /// Parses a production at the start of the input and returns its value
+    /// together with the rest of the input.
+    pub fn parse_prefix<T>(
+        mut self,
+        production: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<(T, &'a str), ParseError> {
+        let value = production(&mut self)?;
+        Ok((value, self.iterator.as_str()))
+    }
+
+    pub fn parse_addr_spec(&mut self) -> Result<AddrSpec, ParseError> {
+        Ok(self.parse_addr_spec_with_spans()?.0.into_owned())
+    }
+
+    /// Parses an address specification and returns it together with the spans
+    /// of the local part and domain, excluding any surrounding CFWS.
+    fn parse_addr_spec_with_spans(
+        &mut self,
+    ) -> Result<(AddrSpecRef<'a>, Range<usize>, Range<usize>), ParseError> {
+        let (addr_spec, local_part, domain) = match self.options.profile {
+            Profile::Rfc5322 => self.parse_rfc5322_addr_spec()?,
+            Profile::Rfc5321 => self.parse_smtp_mailbox_with_spans()?,
+            Profile::Html5 => self.parse_html5_addr_spec()?,
+        };
+        #[cfg(feature = "precis")]
+        let addr_spec = match self.options.precis {
+            Some(profile) => AddrSpecRef {
+                local_part: self.enforce_precis(profile, addr_spec.local_part, &local_part)?,
+                ..addr_spec
+            },
+            None => addr_spec,
+        };
+        if (self.options.hostname || self.options.multiple_labels) && !addr_spec.is_literal() {
+            self.check_hostname(&addr_spec, domain.clone())?;
+        }
+        if self.options.global_literals && addr_spec.is_literal() {
+            if let Some(class) = DomainLiteral::classify(addr_spec.domain())
+                .and_then(|literal| literal.ip_class())
+                .filter(|class| !class.is_global())
+            {
+                return Err(ParseError::new(
+                    ParseErrorKind::RestrictedAddressLiteral { class },
+                    "address literal is not globally reachable",
+                    domain,
+                    &[],
+                ));
+            }
+        }
+        if self.options.length_limits {
+            self.check_length_limits(&addr_spec, local_part.clone(), domain.clone())?;
+        }
+        if !self.options.default_ignorables {
+            self.check_default_ignorables(addr_spec.local_part(), local_part.clone())?;
+            self.check_default_ignorables(addr_spec.domain(), domain.clone())?;
+        }
+        #[cfg(feature = "security")]
+        if let Some(level) = self.options.restriction_level {
+            self.check_restriction_level(&addr_spec, level, local_part.clone(), domain.clone())?;
+        }
+        if self.diagnostics.is_some() {
+            self.diagnose_addr_spec(&addr_spec, local_part.clone(), domain.clone());
+        }
+        Ok((addr_spec, local_part, domain))
+    }
+
+    fn parse_rfc5322_addr_spec(
+        &mut self,
+    ) -> Result<(AddrSpecRef<'a>, Range<usize>, Range<usize>), ParseError> {
+        self.parse_addr_spec_cfws()?;
+        let local_part_start = self.position();
+        let local_part = self.parse_local_part()?;
+        let local_part_end = self.position();
+        self.parse_addr_spec_cfws()?;
+        // Only a dot-atom may be continued with further characters.
+        let expected: &'static [Token] = if !self.input[..local_part_end].ends_with('"') {
+            &[Token::Atext, Token::Char('.'), Token::Char('@')]
+        } else if self.options.obsolete {
+            &[Token::Char('.'), Token::Char('@')]
+        } else {
+            &[Token::Char('@')]
+        };
+        self.skip_at(expected)?;
+        self.parse_addr_spec_cfws()?;
+        if self.position() - local_part_end > 1 {
+            self.diagnose(DiagnosticKind::CfwsNearAt, local_part_end..self.position());
+        }
+        let domain_start = self.position();
+        let (domain, literal) = self.parse_domain()?;
+        let domain_end = self.position();
+        self.parse_addr_spec_cfws()?;
+
+        Ok((
+            AddrSpecRef {
+                local_part,
+                domain,
+                literal,
+            },
+            local_part_start..local_part_end,
+            domain_start..domain_end,
+        ))
+    }
+
+    /// Collects the diagnostics that depend on the parsed address
+    /// specification as a whole, i.e. literals and length limits.
+    fn diagnose_addr_spec(
+        &mut self,
+        addr_spec: &AddrSpecRef<'a>,
+        local_part: Range<usize>,
+        domain: Range<usize>,
+    ) {
+        if addr_spec.is_literal() {
+            if smtp::is_address_literal(addr_spec.domain()) {
+                self.diagnose(DiagnosticKind::AddressLiteral, domain.clone());
+            } else {
+                self.diagnose(DiagnosticKind::DomainLiteral, domain.clone());
+            }
+        }
+
+        let (local_part_len, domain_len) = serialized_lengths(addr_spec);
+        if local_part_len > 64 {
+            self.diagnose(DiagnosticKind::LocalPartTooLong, local_part.clone());
+        }
+        if domain_len > 255 {
+            self.diagnose(DiagnosticKind::DomainTooLong, domain.clone());
+        } else if !addr_spec.is_literal()
+            && addr_spec.domain().split('.').any(|label| label.len() > 63)
+        {
+            self.diagnose(DiagnosticKind::LabelTooLong, domain.clone());
+        }
+        if local_part_len + 1 + domain_len > 254 {
+            self.diagnose(DiagnosticKind::AddressTooLong, local_part.start..domain.end);
+        }
+    }
+
+    /// Enforces the PRECIS profile on the local part.
+    #[cfg(feature = "precis")]
+    fn enforce_precis(
+        &self,
+        profile: PrecisProfile,
+        value: Cow<'a, str>,
+        span: &Range<usize>,
+    ) -> Result<Cow<'a, str>, ParseError> {
+        let input = &self.input[span.clone()];
+        let unquoted = input
+            .strip_prefix('"')
+            .and_then(|input| input.strip_suffix('"'));
+        let offset = if input == value {
+            Some(span.start)
+        } else if unquoted == Some(&value) {
+            Some(span.start + 1)
+        } else {
+            None
+        };
+        profile.enforce(value).map_err(|error| match offset {
+            Some(offset) => error.offset(offset),
+            // The local part was unescaped or normalized, so only the whole
+            // local part can be pointed at.
+            None => ParseError::new(
+                error.kind(),
+                error.message(),
+                span.clone(),
+                error.expected(),
+            ),
         })
     }
 
-    #[cfg(feature = "white-spaces")]
-    fn parse_cfws(&mut self) -> Result<(), ParseError> {
-        self.skip_fws();
-        #[cfg(feature = "comments")]
-        while self.eat_chr('(') {
-            self.parse_comment()?;
-            self.skip_fws();
+    /// Checks that the value does not contain default-ignorable code points.
+    fn check_default_ignorables(&self, value: &str, span: Range<usize>) -> Result<(), ParseError> {
+        let Some(chr) = value
+            .chars()
+            .find(|&chr| unicode::is_default_ignorable(chr))
+        else {
+            return Ok(());
+        };
+        let span = self.input[span.clone()]
+            .find(chr)
+            .map_or(span.clone(), |index| {
+                span.start + index..span.start + index + chr.len_utf8()
+            });
+        Err(ParseError::new(
+            ParseErrorKind::InvalidCharacter { found: chr },
+            "default-ignorable character",
+            span,
+            &[],
+        ))
+    }
+
+    /// Checks the restriction levels of the local part and the labels of the
+    /// domain.
+    #[cfg(feature = "security")]
+    fn check_restriction_level(
+        &self,
+        addr_spec: &AddrSpecRef<'a>,
+        level: RestrictionLevel,
+        local_part: Range<usize>,
+        domain: Range<usize>,
+    ) -> Result<(), ParseError> {
+        let error = |span| {
+            ParseError::new(
+                ParseErrorKind::RestrictionLevelNotMet,
+                "restriction level not met",
+                span,
+                &[],
+            )
+        };
+        if RestrictionLevel::of_local_part(addr_spec.local_part()) > level {
+            return Err(error(local_part));
+        }
+        if addr_spec.is_literal() {
+            return Ok(());
+        }
+        for (label, start) in labels(self.input, addr_spec.domain(), &domain) {
+            if RestrictionLevel::of_label(label) > level {
+                return Err(error(label_span(start, 0..label.len(), &domain)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the length limits of [Section 4.5.3.1, RFC
+    /// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.5.3.1).
+    fn check_length_limits(
+        &self,
+        addr_spec: &AddrSpecRef<'a>,
+        local_part: Range<usize>,
+        domain: Range<usize>,
+    ) -> Result<(), ParseError> {
+        let (local_part_len, domain_len) = serialized_lengths(addr_spec);
+        if local_part_len > 64 {
+            return Err(ParseError::new(
+                ParseErrorKind::LocalPartTooLong,
+                "local part longer than 64 octets",
+                local_part,
+                &[],
+            ));
+        }
+        if domain_len > 255 {
+            return Err(ParseError::new(
+                ParseErrorKind::DomainTooLong,
+                "domain longer than 255 octets",
+                domain,
+                &[],
+            ));
+        }
+        if !addr_spec.is_literal() {
+            for (label, start) in labels(self.input, addr_spec.domain(), &domain) {
+                if label.len() > 63 {
+                    return Err(ParseError::new(
+                        ParseErrorKind::LabelTooLong,
+                        "label longer than 63 octets",
+                        label_span(start, 0..label.len(), &domain),
+                        &[],
+                    ));
+                }
+            }
+        }
+        if local_part_len + 1 + domain_len > 254 {
+            return Err(ParseError::new(
+                ParseErrorKind::AddressTooLong,
+                "address longer than 254 octets",
+                local_part.start..domain.end,
+                &[],
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the domain is a valid hostname as defined in [Section 2.1,
+    /// RFC 1123](https://www.rfc-editor.org/rfc/rfc1123#section-2.1), and
+    /// that it has multiple labels if required.
+    fn check_hostname(
+        &self,
+        addr_spec: &AddrSpecRef<'a>,
+        domain: Range<usize>,
+    ) -> Result<(), ParseError> {
+        // With IDNA support, U-labels are checked in their ASCII form.
+        #[cfg(feature = "idna")]
+        let ascii = if self.options.hostname && !addr_spec.domain().is_ascii() {
+            idn::hostname_to_ascii(addr_spec.domain()).map_err(|error| {
+                if self.input[domain.clone()] == *addr_spec.domain() {
+                    error.offset(domain.start)
+                } else {
+                    ParseError::new(
+                        error.kind(),
+                        error.message(),
+                        domain.clone(),
+                        error.expected(),
+                    )
+                }
+            })?
+        } else {
+            Cow::Borrowed(addr_spec.domain())
+        };
+        #[cfg(not(feature = "idna"))]
+        let ascii = Cow::Borrowed(addr_spec.domain());
+
+        let mut tld = None;
+        let mut label_count = 0usize;
+        for ((label, start), ascii_label) in
+            labels(self.input, addr_spec.domain(), &domain).zip(ascii.split('.'))
+        {
+            if !self.options.hostname {
+                label_count += 1;
+                continue;
+            }
+            // Errors in converted labels refer to the whole label.
+            let span = |range: Range<usize>| {
+                let range = if ascii_label == label {
+                    range
+                } else {
+                    0..label.len()
+                };
+                label_span(start, range, &domain)
+            };
+            if let Some((index, chr)) = ascii_label
+                .char_indices()
+                .find(|&(_, chr)| !smtp::is_ldh_or_non_ascii(chr))
+            {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: chr },
+                    "invalid character in hostname",
+                    span(index..index + chr.len_utf8()),
+                    &[Token::LetterOrDigit, Token::Char('-')],
+                ));
+            }
+            if ascii_label.starts_with('-') {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: '-' },
+                    "invalid hyphen in hostname",
+                    span(0..1),
+                    &[Token::LetterOrDigit],
+                ));
+            }
+            if ascii_label.ends_with('-') {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: '-' },
+                    "invalid hyphen in hostname",
+                    span(ascii_label.len() - 1..ascii_label.len()),
+                    &[Token::LetterOrDigit],
+                ));
+            }
+            if unicode::a_label_len(ascii_label) > 63 {
+                return Err(ParseError::new(
+                    ParseErrorKind::LabelTooLong,
+                    "label longer than 63 octets",
+                    span(0..ascii_label.len()),
+                    &[],
+                ));
+            }
+            tld = Some((ascii_label, label, start));
+            label_count += 1;
+        }
+
+        if let Some((tld, label, start)) = tld {
+            if tld.bytes().all(|byte| byte.is_ascii_digit()) {
+                return Err(ParseError::new(
+                    ParseErrorKind::NumericTopLevelDomain,
+                    "numeric top-level domain",
+                    label_span(start, 0..label.len(), &domain),
+                    &[Token::LetterOrDigit],
+                ));
+            }
+        }
+        if self.options.multiple_labels && label_count < 2 {
+            return Err(ParseError::new(
+                ParseErrorKind::SingleLabelDomain,
+                "expected multiple labels in domain",
+                domain,
+                &[Token::Char('.')],
+            ));
+        }
+        Ok(())
+    }
+
+    fn parse_mailbox_impl(&mut self) -> Result<Mailbox, ParseError> {
+        // A mailbox is either a name address or an address specification. We
+        // only know which one after the (optional) display name, so we
+        // backtrack if there is no angle address after it. Errors in the
+        // display name are errors of the address specification as well, so
+        // they are reported right away.
+        let checkpoint = self.iterator.clone();
+        let display_name = self.parse_phrase()?;
+        if self.eat_chr('<') {
+            if self.options.obsolete {
+                self.parse_obs_route()?;
+            }
+            let addr_spec = self.parse_addr_spec()?;
+            if !self.eat_chr('>') {
+                return Err(self.unexpected(
+                    "expected '>' for angle address",
+                    &[Token::Char('>')],
+                    ParseErrorKind::UnterminatedAngleAddress,
+                ));
+            }
+            self.parse_phrase_cfws()?;
+            return Ok(Mailbox::from_parts(display_name, addr_spec));
+        }
+        self.iterator = checkpoint;
+        Ok(Mailbox::from(self.parse_addr_spec()?))
+    }
+
+    /// Parses (and discards) an obsolete source route in an angle address,
+    /// e.g. `<@a.example,@b.example:jdoe@c.example>`.
+    fn parse_obs_route(&mut self) -> Result<(), ParseError> {
+        let checkpoint = self.iterator.clone();
+        loop {
+            self.parse_phrase_cfws()?;
+            if !self.eat_chr(',') {
+                break;
+            }
+        }
+        if !self.eat_chr('@') {
+            self.iterator = checkpoint;
+            return Ok(());
+        }
+        loop {
+            self.parse_phrase_cfws()?;
+            self.parse_domain()?;
+            loop {
+                self.parse_phrase_cfws()?;
+                if self.eat_chr(':') {
+                    return Ok(());
+                }
+                if !self.eat_chr(',') {
+                    return Err(self.unexpected(
+                        "expected ':' for route",
+                        &[Token::Char(':'), Token::Char(',')],
+                        ParseErrorKind::UnexpectedEnd,
+                    ));
+                }
+                self.parse_phrase_cfws()?;
+                if self.eat_chr('@') {
+                    break;
+                }
+            }
+        }
+    }
+
+    pub fn parse_address(mut self) -> Result<Address, ParseError> {
+        let address = self.parse_address_impl()?;
+        self.check_end("expected end of address")?;
+        Ok(address)
+    }
+
+    /// Parses an address list, recovering from invalid entries by skipping
+    /// to the next top-level comma.
+    pub fn parse_address_list(mut self) -> Vec<Result<Address, ParseError>> {
+        let mut addresses = Vec::new();
+        loop {
+            let checkpoint = self.iterator.clone();
+            if self.options.obsolete && self.parse_phrase_cfws().is_ok() {
+                // The obsolete syntax allows empty entries as long as there is
+                // at least one address.
+                let input = self.iterator.as_str();
+                if input.starts_with(',') || (input.is_empty() && !addresses.is_empty()) {
+                    if !self.eat_chr(',') {
+                        return addresses;
+                    }
+                    continue;
+                }
+            }
+            self.iterator = checkpoint.clone();
+            let address = self.parse_address_list_entry();
+            if address.is_err() {
+                self.iterator = checkpoint;
+                self.skip_address_list_entry();
+            }
+            addresses.push(address);
+            if !self.eat_chr(',') {
+                return addresses;
+            }
+        }
+    }
+
+    fn parse_address_list_entry(&mut self) -> Result<Address, ParseError> {
+        self.parse_phrase_cfws()?;
+        let address = self.parse_address_impl()?;
+        self.parse_phrase_cfws()?;
+        let input = self.iterator.as_str();
+        if input.is_empty() || input.starts_with(',') {
+            return Ok(address);
+        }
+        Err(self.unexpected(
+            "expected ',' or end of address list",
+            &[Token::Char(','), Token::End],
+            ParseErrorKind::UnexpectedEnd,
+        ))
+    }
+
+    fn skip_address_list_entry(&mut self) {
+        let mut quoted = false;
+        let mut comment_level = 0usize;
+        let mut angle = false;
+        let mut group = false;
+        loop {
+            let input = self.iterator.as_str();
+            let Some(chr) = self.iterator.next() else {
+                return;
+            };
+            match chr {
+                '\\' if quoted || comment_level > 0 => {
+                    self.iterator.next();
+                }
+                '"' if comment_level == 0 => quoted = !quoted,
+                _ if quoted => {}
+                '(' => comment_level += 1,
+                ')' => comment_level = comment_level.saturating_sub(1),
+                _ if comment_level > 0 => {}
+                '<' => angle = true,
+                '>' => angle = false,
+                _ if angle => {}
+                ':' => group = true,
+                ';' => group = false,
+                ',' if !group => {
+                    self.iterator = input.chars();
+                    return;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_address_impl(&mut self) -> Result<Address, ParseError> {
+        // Both groups and name addresses start with a display name, so we
+        // backtrack if the display name is not followed by a colon.
+        let checkpoint = self.iterator.clone();
+        if let Ok(Some(display_name)) = self.parse_phrase() {
+            if self.eat_chr(':') {
+                let mailboxes = self.parse_group_list()?;
+                return Ok(Address::Group(Group::from_parts(display_name, mailboxes)));
+            }
+        }
+        self.iterator = checkpoint;
+        Ok(Address::Mailbox(self.parse_mailbox_impl()?))
+    }
+
+    fn parse_group_list(&mut self) -> Result<Vec<Mailbox>, ParseError> {
+        let mut mailboxes = Vec::new();
+        loop {
+            self.parse_phrase_cfws()?;
+            if (mailboxes.is_empty() || self.options.obsolete) && self.eat_chr(';') {
+                break;
+            }
+            // The obsolete syntax allows empty entries.
+            if !(self.options.obsolete && self.iterator.as_str().starts_with(',')) {
+                mailboxes.push(self.parse_mailbox_impl()?);
+                self.parse_phrase_cfws()?;
+                if self.eat_chr(';') {
+                    break;
+                }
+            }
+            if !self.eat_chr(',') {
+                return Err(self.unexpected(
+                    "expected ',' or ';' in group",
+                    &[Token::Char(','), Token::Char(';')],
+                    ParseErrorKind::UnexpectedEnd,
+                ));
+            }
+        }
+        self.parse_phrase_cfws()?;
+        Ok(mailboxes)
+    }
+
+    fn parse_phrase(&mut self) -> Result<Option<String>, ParseError> {
+        self.parse_phrase_cfws()?;
+
+        let mut phrase = String::new();
+        let mut words = 0usize;
+        loop {
+            let separator = if words == 0 { "" } else { " " };
+            if self.eat_chr('"') {
+                phrase.push_str(separator);
+                self.parse_quoted_phrase(&mut phrase)?;
+            } else {
+                let input = self.iterator.as_str();
+                // Periods are only allowed after the first word of an
+                // obsolete phrase.
+                let size = if self.options.obsolete && (words > 0 || !input.starts_with('.')) {
+                    input.find(is_not_atext)
+                } else {
+                    input.find(is_not_phrase_atext)
+                }
+                .unwrap_or(input.len());
+                if size == 0 {
+                    break;
+                }
+                phrase.push_str(separator);
+                phrase.push_str(&input[..size]);
+                self.iterator = input[size..].chars();
+            }
+            words += 1;
+            self.parse_phrase_cfws()?;
+        }
+
+        if words == 0 {
+            return Ok(None);
+        }
+        Ok(Some(self.normalize(phrase).into_owned()))
+    }
+
+    fn parse_quoted_phrase(&mut self, phrase: &mut String) -> Result<(), ParseError> {
+        while let Some(chr) = self.iterator.next() {
+            let chr = match chr {
+                '"' => return Ok(()),
+                '\\' => self.parse_quoted_pair()?,
+                // Folding white spaces are unfolded, i.e. only the line break
+                // is removed.
+                '\r' if matches!(self.iterator.as_str().as_bytes(), [b'\n', b' ' | b'\t', ..]) => {
+                    self.iterator.next();
+                    continue;
+                }
+                chr if is_ascii_control_and_not_htab(chr) => {
+                    return Err(self.invalid_character(
+                        chr,
+                        "invalid character in quoted display name",
+                        &[Token::Qtext, Token::Char('"')],
+                    ))
+                }
+                chr => chr,
+            };
+            phrase.push(chr);
+        }
+
+        Err(self.unexpected(
+            "expected '\"' for quoted display name",
+            &[Token::Char('"')],
+            ParseErrorKind::UnterminatedQuote,
+        ))
+    }
+
+    pub fn parse_cfws(&mut self) -> Result<(), ParseError> {
+        self.parse_cfws_with(Self::skip_fws)
+    }
+
+    /// Parses CFWS around the local part and domain. The obsolete syntax
+    /// allows CFWS around every word, so white spaces are always allowed
+    /// there.
+    fn parse_addr_spec_cfws(&mut self) -> Result<(), ParseError> {
+        if self.options.obsolete {
+            return self.parse_phrase_cfws();
+        }
+        self.parse_cfws()
+    }
+
+    /// Parses CFWS in places where white spaces are always allowed, e.g.
+    /// between the words of a phrase.
+    fn parse_phrase_cfws(&mut self) -> Result<(), ParseError> {
+        self.parse_cfws_with(Self::skip_fws_unconditionally)
+    }
+
+    fn parse_cfws_with(&mut self, skip_fws: fn(&mut Self)) -> Result<(), ParseError> {
+        skip_fws(self);
+        if self.options.comments {
+            loop {
+                let start = self.position();
+                if !self.eat_chr('(') {
+                    break;
+                }
+                let checkpoint = self.diagnostics_checkpoint();
+                self.parse_comment()?;
+                // White spaces inside a comment are part of the comment.
+                self.restore_diagnostics(checkpoint);
+                self.diagnose(DiagnosticKind::Comment, start..self.position());
+                skip_fws(self);
+            }
         }
         Ok(())
     }
 
-    #[cfg(feature = "white-spaces")]
     fn skip_fws(&mut self) {
-        self.skip_ws();
-        if !self.eat_str("\r\n") {
-            return;
+        if self.options.white_spaces {
+            self.skip_fws_unconditionally();
         }
+    }
+
+    fn skip_fws_unconditionally(&mut self) {
+        let start = self.position();
         self.skip_ws();
+        while self.eat_str("\r\n") {
+            self.skip_ws();
+            // The obsolete syntax allows multiple line breaks.
+            if !self.options.obsolete {
+                break;
+            }
+        }
+        if self.position() != start {
+            self.diagnose(DiagnosticKind::FoldingWhiteSpace, start..self.position());
+        }
     }
 
-    #[cfg(feature = "white-spaces")]
     fn skip_ws(&mut self) {
         loop {
             if !self.eat_slice([' ', '\t']) {
@@ -120,7 +995,6 @@ impl<'a> Parser<'a> {
         }
     }
 
-    #[cfg(feature = "white-spaces")]
     fn eat_slice<const N: usize>(&mut self, pattern: [char; N]) -> bool {
         if self.iterator.as_str().starts_with(pattern) {
             self.iterator.next();
@@ -129,7 +1003,6 @@ impl<'a> Parser<'a> {
         false
     }
 
-    #[cfg(feature = "white-spaces")]
     fn eat_str(&mut self, pattern: &str) -> bool {
         if let Some(input) = self.iterator.as_str().strip_prefix(pattern) {
             self.iterator = input.chars();
@@ -146,9 +1019,7 @@ impl<'a> Parser<'a> {
         false
     }
 
-    #[cfg(feature = "comments")]
     fn parse_comment(&mut self) -> Result<(), ParseError> {
-        #[cfg(feature = "white-spaces")]
         self.skip_fws();
 
         let mut nest_level = 1usize;
@@ -168,42 +1039,155 @@ impl<'a> Parser<'a> {
                 }
                 chr => {
                     if is_ascii_control_or_space(chr) {
-                        return Err(self.error("invalid character in comment", -1));
+                        return Err(self.invalid_character(
+                            chr,
+                            "invalid character in comment",
+                            &[Token::Ctext, Token::Char(')')],
+                        ));
                     }
                 }
             }
 
-            #[cfg(feature = "white-spaces")]
             self.skip_fws();
         }
 
-        Err(self.error("expected ')' for comment", 0))
+        Err(self.unexpected(
+            "expected ')' for comment",
+            &[Token::Char(')')],
+            ParseErrorKind::UnterminatedComment,
+        ))
     }
 
     fn parse_quoted_pair(&mut self) -> Result<char, ParseError> {
         match self.iterator.next() {
             Some(chr) if !is_ascii_control_and_not_htab(chr) => Ok(chr),
-            Some(_) => Err(self.error("invalid character in quoted pair", -1)),
-            None => Err(self.error("unexpected end of quoted pair", 0)),
+            Some(chr) => Err(self.invalid_character(
+                chr,
+                "invalid character in quoted pair",
+                &[Token::Vchar],
+            )),
+            None => Err(self.unexpected(
+                "unexpected end of quoted pair",
+                &[Token::Vchar],
+                ParseErrorKind::UnexpectedEnd,
+            )),
         }
     }
 
-    fn parse_local_part(&mut self) -> Result<String, ParseError> {
+    fn parse_local_part(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        if self.options.obsolete {
+            return self.parse_obs_local_part();
+        }
+        let start = self.position();
         if !self.eat_chr('"') {
-            return Ok(unicode::normalize(
-                self.parse_dot_atom("empty label in local part")?,
-            ));
+            let dot_atom = self.parse_dot_atom("empty label in local part")?;
+            return Ok(self.normalize(dot_atom));
         }
-        Ok(unicode::normalize(self.parse_quoted_string(
+        let quoted_string = self.parse_quoted_string(
             "invalid character in quoted local part",
             "expected '\"' for quoted local part",
-        )?))
+        )?;
+        self.diagnose(DiagnosticKind::QuotedLocalPart, start..self.position());
+        Ok(self.normalize(quoted_string))
+    }
+
+    /// Parses an obsolete local part, i.e. a sequence of atoms and quoted
+    /// strings separated by periods and optionally surrounded by CFWS.
+    fn parse_obs_local_part(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        let start = self.position();
+        let mut local_part = String::new();
+        let mut quoted = false;
+        let mut obsolete = false;
+        let mut words = 0usize;
+        loop {
+            if self.eat_chr('"') {
+                local_part.push_str(&self.parse_quoted_string(
+                    "invalid character in quoted local part",
+                    "expected '\"' for quoted local part",
+                )?);
+                quoted = true;
+            } else {
+                local_part.push_str(self.parse_atom("empty label in local part")?);
+            }
+            words += 1;
+            let end = self.position();
+            if !self.eat_obs_period()? {
+                // Only a single quoted string is valid in the modern syntax.
+                if quoted && (obsolete || words > 1) {
+                    self.diagnose(DiagnosticKind::ObsoleteLocalPart, start..end);
+                } else if quoted {
+                    self.diagnose(DiagnosticKind::QuotedLocalPart, start..end);
+                } else if obsolete {
+                    self.diagnose(DiagnosticKind::ObsoleteLocalPart, start..end);
+                }
+                return Ok(self.normalize(local_part));
+            }
+            obsolete |= self.position() - end > 1;
+            local_part.push('.');
+        }
+    }
+
+    /// Parses an obsolete domain, i.e. a sequence of atoms separated by
+    /// periods and optionally surrounded by CFWS.
+    fn parse_obs_domain(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        let start = self.position();
+        let mut domain = String::new();
+        let mut obsolete = false;
+        loop {
+            domain.push_str(self.parse_atom("empty label in domain")?);
+            let end = self.position();
+            if !self.eat_obs_period()? {
+                if obsolete {
+                    self.diagnose(DiagnosticKind::ObsoleteDomain, start..end);
+                }
+                return Ok(self.normalize(domain));
+            }
+            obsolete |= self.position() - end > 1;
+            domain.push('.');
+        }
+    }
+
+    fn eat_obs_period(&mut self) -> Result<bool, ParseError> {
+        let checkpoint = self.iterator.clone();
+        let diagnostics_checkpoint = self.diagnostics_checkpoint();
+        self.parse_phrase_cfws()?;
+        if !self.eat_chr('.') {
+            // Trailing CFWS belongs to the surrounding address specification.
+            self.iterator = checkpoint;
+            self.restore_diagnostics(diagnostics_checkpoint);
+            return Ok(false);
+        }
+        self.parse_phrase_cfws()?;
+        Ok(true)
+    }
+
+    fn parse_atom(&mut self, empty_atom_error_text: &'static str) -> Result<&'a str, ParseError> {
+        let input = self.iterator.as_str();
+        let size = input.find(is_not_phrase_atext).unwrap_or(input.len());
+        if size == 0 {
+            return Err(self.empty_label(empty_atom_error_text, 0));
+        }
+        self.iterator = input[size..].chars();
+        Ok(&input[..size])
+    }
+
+    #[inline]
+    fn normalize<S>(&self, value: S) -> Cow<'a, str>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let value = value.into();
+        #[cfg(feature = "normalization")]
+        if self.options.normalization {
+            return unicode::normalize_cow(value);
+        }
+        value
     }
 
     pub fn parse_dot_atom(
         &mut self,
         empty_label_error_text: &'static str,
-    ) -> Result<&str, ParseError> {
+    ) -> Result<&'a str, ParseError> {
         let input = self.iterator.as_str();
         let size = input.find(is_not_atext).unwrap_or(input.len());
 
@@ -213,28 +1197,58 @@ impl<'a> Parser<'a> {
             .find(|label| label.is_empty())
             .map(|label| label.as_ptr() as usize - dot_atom.as_ptr() as usize)
         {
-            return Err(self.error(empty_label_error_text, offset as isize));
+            return Err(self.empty_label(empty_label_error_text, offset));
         }
 
         self.iterator = input[size..].chars();
         Ok(dot_atom)
     }
 
+    /// Parses a quoted string including its opening quote.
+    pub fn parse_quoted_string_token(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        if !self.eat_chr('"') {
+            return Err(self.unexpected(
+                "expected '\"' for quoted string",
+                &[Token::Char('"')],
+                ParseErrorKind::UnexpectedEnd,
+            ));
+        }
+        self.parse_quoted_string(
+            "invalid character in quoted string",
+            "expected '\"' for quoted string",
+        )
+    }
+
     fn parse_quoted_string(
         &mut self,
         invalid_character_error_text: &'static str,
         expected_quote_error_text: &'static str,
-    ) -> Result<String, ParseError> {
-        #[cfg(feature = "white-spaces")]
+    ) -> Result<Cow<'a, str>, ParseError> {
+        // Quoted strings without quoted pairs and white spaces can be borrowed
+        // from the input.
+        let input = self.iterator.as_str();
+        if let Some(size) =
+            input.find(|chr| chr == '"' || chr == '\\' || is_ascii_control_or_space(chr))
+        {
+            if input[size..].starts_with('"') {
+                self.iterator = input[size + 1..].chars();
+                return Ok(Cow::Borrowed(&input[..size]));
+            }
+        }
+
         self.skip_fws();
 
         let mut quoted_string = unsafe { FixedVec::new(self.iterator.as_str().len()) };
         while let Some(chr) = self.iterator.next() {
             let chr = match chr {
-                '"' => return Ok(quoted_string.into()),
+                '"' => return Ok(Cow::Owned(quoted_string.into())),
                 '\\' => self.parse_quoted_pair()?,
                 chr if is_ascii_control_or_space(chr) => {
-                    return Err(self.error(invalid_character_error_text, -1))
+                    return Err(self.invalid_character(
+                        chr,
+                        invalid_character_error_text,
+                        &[Token::Qtext, Token::Char('"')],
+                    ))
                 }
                 chr => chr,
             };
@@ -242,55 +1256,85 @@ impl<'a> Parser<'a> {
                 quoted_string.extend_char_unchecked(chr);
             }
 
-            #[cfg(feature = "white-spaces")]
             self.skip_fws();
         }
 
-        Err(self.error(expected_quote_error_text, 0))
+        Err(self.unexpected(
+            expected_quote_error_text,
+            &[Token::Char('"')],
+            ParseErrorKind::UnterminatedQuote,
+        ))
     }
 
-    fn skip_at(&mut self) -> Result<(), ParseError> {
+    fn skip_at(&mut self, expected: &'static [Token]) -> Result<(), ParseError> {
         if self.eat_chr('@') {
             return Ok(());
         }
-        Err(self.error("expected '@'", 1))
+        Err(self.unexpected("expected '@'", expected, ParseErrorKind::MissingAt))
     }
 
-    fn parse_domain(&mut self) -> Result<(String, bool), ParseError> {
-        #[cfg(feature = "literals")]
-        if self.eat_chr('[') {
-            return Ok((unicode::normalize(self.parse_domain_literal()?), true));
+    fn parse_domain(&mut self) -> Result<(Cow<'a, str>, bool), ParseError> {
+        if self.options.literals && self.eat_chr('[') {
+            let start = self.position();
+            let domain = self.parse_domain_literal()?;
+            let Some(domain) = canonical_literal(domain) else {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidAddressLiteral,
+                    "invalid address literal",
+                    start..self.position() - 1,
+                    &[Token::AddressLiteral],
+                ));
+            };
+            return Ok((self.normalize(domain), true));
         }
-        Ok((
-            unicode::normalize(self.parse_dot_atom("empty label in domain")?),
-            false,
-        ))
+        if self.options.obsolete {
+            return Ok((self.parse_obs_domain()?, false));
+        }
+        let dot_atom = self.parse_dot_atom("empty label in domain")?;
+        Ok((self.normalize(dot_atom), false))
     }
 
-    #[cfg(all(feature = "literals", not(feature = "white-spaces")))]
-    fn parse_domain_literal(&mut self) -> Result<&str, ParseError> {
-        let input = self.iterator.as_str();
-        let size = input.find(is_not_dtext).unwrap_or(input.len());
-
-        self.iterator = input[size..].chars();
-        if !self.eat_chr(']') {
-            return Err(self.error("expected ']' for domain literal", 0));
+    /// Parses a domain literal including its opening bracket.
+    pub fn parse_domain_literal_token(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        if !self.eat_chr('[') {
+            return Err(self.unexpected(
+                "expected '[' for domain literal",
+                &[Token::Char('[')],
+                ParseErrorKind::UnexpectedEnd,
+            ));
         }
-
-        Ok(&input[..size])
+        self.parse_domain_literal()
     }
 
-    #[cfg(all(feature = "literals", feature = "white-spaces"))]
-    fn parse_domain_literal(&mut self) -> Result<String, ParseError> {
-        #[cfg(feature = "white-spaces")]
+    fn parse_domain_literal(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        if !self.options.white_spaces {
+            let input = self.iterator.as_str();
+            let size = input.find(is_not_dtext).unwrap_or(input.len());
+
+            self.iterator = input[size..].chars();
+            if !self.eat_chr(']') {
+                return Err(self.unexpected(
+                    "expected ']' for domain literal",
+                    &[Token::Dtext, Token::Char(']')],
+                    ParseErrorKind::UnterminatedDomainLiteral,
+                ));
+            }
+
+            return Ok(Cow::Borrowed(&input[..size]));
+        }
+
         self.skip_fws();
 
         let mut domain = unsafe { FixedVec::new(self.iterator.as_str().len()) };
         while let Some(chr) = self.iterator.next() {
             let chr = match chr {
-                ']' => return Ok(domain.into()),
+                ']' => return Ok(Cow::Owned(domain.into())),
                 chr if is_not_dtext(chr) => {
-                    return Err(self.error("invalid character in literal domain", -1))
+                    return Err(self.invalid_character(
+                        chr,
+                        "invalid character in literal domain",
+                        &[Token::Dtext, Token::Char(']')],
+                    ))
                 }
                 chr => chr,
             };
@@ -298,11 +1342,14 @@ impl<'a> Parser<'a> {
                 domain.extend_char_unchecked(chr);
             }
 
-            #[cfg(feature = "white-spaces")]
             self.skip_fws();
         }
 
-        Err(self.error("expected ']' for domain literal", 0))
+        Err(self.unexpected(
+            "expected ']' for domain literal",
+            &[Token::Dtext, Token::Char(']')],
+            ParseErrorKind::UnterminatedDomainLiteral,
+        ))
     }
 
     #[inline]
@@ -310,19 +1357,139 @@ impl<'a> Parser<'a> {
         if self.iterator.as_str().is_empty() {
             return Ok(());
         }
-        Err(self.error(message, 0))
+        Err(ParseError::new(
+            ParseErrorKind::TrailingInput,
+            message,
+            self.position()..self.input.len(),
+            &[Token::End],
+        ))
+    }
+
+    #[inline]
+    fn diagnose(&mut self, kind: DiagnosticKind, span: Range<usize>) {
+        if let Some(diagnostics) = &mut self.diagnostics {
+            diagnostics.push(Diagnostic::new(kind, span));
+        }
+    }
+
+    #[inline]
+    fn diagnostics_checkpoint(&self) -> usize {
+        self.diagnostics.as_ref().map_or(0, Vec::len)
+    }
+
+    #[inline]
+    fn restore_diagnostics(&mut self, checkpoint: usize) {
+        if let Some(diagnostics) = &mut self.diagnostics {
+            diagnostics.truncate(checkpoint);
+        }
+    }
+
+    #[inline]
+    fn position(&self) -> usize {
+        self.input.len() - self.iterator.as_str().len()
     }
 
-    fn error(&self, message: &'static str, offset: isize) -> ParseError {
-        ParseError(
+    /// Returns an error referring to the next character, or to the end of the
+    /// input if there is none.
+    fn error(
+        &self,
+        kind: ParseErrorKind,
+        message: &'static str,
+        expected: &'static [Token],
+    ) -> ParseError {
+        let start = self.position();
+        let end = start + self.iterator.clone().next().map_or(0, char::len_utf8);
+        ParseError::new(kind, message, start..end, expected)
+    }
+
+    /// Returns an error for the unexpected next character, or an error of the
+    /// given kind if the input ended.
+    fn unexpected(
+        &self,
+        message: &'static str,
+        expected: &'static [Token],
+        end_kind: ParseErrorKind,
+    ) -> ParseError {
+        match self.iterator.clone().next() {
+            Some(chr) => ParseError::invalid_character(chr, message, self.position(), expected),
+            None => self.error(end_kind, message, expected),
+        }
+    }
+
+    /// Returns an error for the character that was just consumed.
+    fn invalid_character(
+        &self,
+        chr: char,
+        message: &'static str,
+        expected: &'static [Token],
+    ) -> ParseError {
+        ParseError::invalid_character(chr, message, self.position() - chr.len_utf8(), expected)
+    }
+
+    /// Returns an error for an empty label at the given offset from the
+    /// current position.
+    fn empty_label(&self, message: &'static str, offset: usize) -> ParseError {
+        let index = self.position() + offset;
+        ParseError::new(
+            ParseErrorKind::EmptyLabel,
             message,
-            (self.input.len() - self.iterator.as_str().len())
-                .checked_add_signed(offset)
-                .unwrap(),
+            index..index,
+            &[Token::Atext],
         )
     }
 }
 
+/// Returns the canonical form of the domain literal, or `None` if it looks like
+/// but is not a valid IP address literal.
+pub fn canonical_literal(literal: Cow<'_, str>) -> Option<Cow<'_, str>> {
+    match DomainLiteral::classify(&literal)? {
+        canonical @ (DomainLiteral::Ipv4(_) | DomainLiteral::Ipv6(_)) => {
+            let canonical = canonical.to_string();
+            if canonical == *literal {
+                return Some(literal);
+            }
+            Some(Cow::Owned(canonical))
+        }
+        _ => Some(literal),
+    }
+}
+
+/// Returns the labels of the domain together with their start in the input.
+///
+/// Obsolete or normalized domains do not map onto the input, so their labels
+/// have no start.
+fn labels<'b>(
+    input: &str,
+    domain: &'b str,
+    span: &Range<usize>,
+) -> impl Iterator<Item = (&'b str, Option<usize>)> {
+    let mut start = (input[span.clone()] == *domain).then_some(span.start);
+    domain.split('.').map(move |label| {
+        let label_start = start;
+        start = start.map(|start| start + label.len() + 1);
+        (label, label_start)
+    })
+}
+
+/// Returns the span of the range within a label, or the span of the domain if
+/// the label does not map onto the input.
+fn label_span(start: Option<usize>, range: Range<usize>, domain: &Range<usize>) -> Range<usize> {
+    start.map_or(domain.clone(), |start| {
+        start + range.start..start + range.end
+    })
+}
+
+/// Returns the lengths of the serialized local part and domain in octets.
+fn serialized_lengths(addr_spec: &AddrSpecRef<'_>) -> (usize, usize) {
+    let local_part_len = if addr_spec.is_quoted() {
+        quote(addr_spec.local_part()).len() + 2
+    } else {
+        addr_spec.local_part().len()
+    };
+    let domain_len = addr_spec.domain().len() + if addr_spec.is_literal() { 2 } else { 0 };
+    (local_part_len, domain_len)
+}
+
 pub struct FixedVec<T> {
     ptr: *mut T,
     len: usize,
@@ -331,11 +1498,13 @@ pub struct FixedVec<T> {
 
 impl<T> FixedVec<T> {
     pub unsafe fn new(cap: usize) -> Self {
-        Self {
-            ptr: unsafe { std::alloc::alloc(std::alloc::Layout::array::<T>(cap).unwrap()).cast() },
-            len: 0,
-            cap,
-        }
+        // Allocating zero bytes is undefined behavior.
+        let ptr = if cap == 0 {
+            std::ptr::NonNull::dangling().as_ptr()
+        } else {
+            unsafe { std::alloc::alloc(std::alloc::Layout::array::<T>(cap).unwrap()).cast() }
+        };
+        Self { ptr, len: 0, cap }
     }
 
     unsafe fn extend_unchecked(&mut self, slice: &[T]) {
@@ -355,6 +1524,9 @@ impl FixedVec<u8> {
 
 impl<T> Drop for FixedVec<T> {
     fn drop(&mut self) {
+        if self.cap == 0 {
+            return;
+        }
         unsafe {
             std::alloc::dealloc(
                 self.ptr.cast(),
@@ -374,7 +1546,7 @@ impl From<FixedVec<u8>> for String {
 #[cfg(test)]
 mod tests {
     mod dot_atoms {
-        use super::super::{ParseError, Parser};
+        use super::super::{ParseError, ParseErrorKind, Parser, Token};
 
         #[test]
         fn test_parse_local_part() {
@@ -385,7 +1557,12 @@ mod tests {
         fn test_parse_empty_local_part() {
             assert_eq!(
                 Parser::new("").parse_local_part().unwrap_err(),
-                ParseError("empty label in local part", 0)
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in local part",
+                    0..0,
+                    &[Token::Atext]
+                )
             )
         }
 
@@ -393,7 +1570,12 @@ mod tests {
         fn test_parse_local_part_with_empty_label_in_front() {
             assert_eq!(
                 Parser::new(".test").parse_local_part().unwrap_err(),
-                ParseError("empty label in local part", 0)
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in local part",
+                    0..0,
+                    &[Token::Atext]
+                )
             )
         }
 
@@ -401,7 +1583,12 @@ mod tests {
         fn test_parse_local_part_with_empty_label_in_middle() {
             assert_eq!(
                 Parser::new("te..st").parse_local_part().unwrap_err(),
-                ParseError("empty label in local part", 3)
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in local part",
+                    3..3,
+                    &[Token::Atext]
+                )
             )
         }
 
@@ -409,7 +1596,25 @@ mod tests {
         fn test_parse_local_part_with_empty_label_in_back() {
             assert_eq!(
                 Parser::new("test.").parse_local_part().unwrap_err(),
-                ParseError("empty label in local part", 5)
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in local part",
+                    5..5,
+                    &[Token::Atext]
+                )
+            )
+        }
+
+        #[test]
+        fn test_parse_local_part_with_semicolon() {
+            assert_eq!(
+                Parser::new("te;st@example.com").parse().unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: ';' },
+                    "expected '@'",
+                    2..3,
+                    &[Token::Atext, Token::Char('.'), Token::Char('@')]
+                )
             )
         }
 
@@ -417,7 +1622,7 @@ mod tests {
         fn test_parse_domain() {
             assert_eq!(
                 Parser::new("test").parse_domain().unwrap(),
-                ("test".to_string(), false)
+                ("test".into(), false)
             )
         }
 
@@ -425,7 +1630,12 @@ mod tests {
         fn test_parse_empty_domain() {
             assert_eq!(
                 Parser::new("").parse_domain().unwrap_err(),
-                ParseError("empty label in domain", 0)
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in domain",
+                    0..0,
+                    &[Token::Atext]
+                )
             )
         }
 
@@ -433,7 +1643,12 @@ mod tests {
         fn test_parse_domain_with_empty_label_in_front() {
             assert_eq!(
                 Parser::new(".test").parse_domain().unwrap_err(),
-                ParseError("empty label in domain", 0)
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in domain",
+                    0..0,
+                    &[Token::Atext]
+                )
             )
         }
 
@@ -441,7 +1656,12 @@ mod tests {
         fn test_parse_domain_with_empty_label_in_middle() {
             assert_eq!(
                 Parser::new("te..st").parse_domain().unwrap_err(),
-                ParseError("empty label in domain", 3)
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in domain",
+                    3..3,
+                    &[Token::Atext]
+                )
             )
         }
 
@@ -449,44 +1669,88 @@ mod tests {
         fn test_parse_domain_with_empty_label_in_back() {
             assert_eq!(
                 Parser::new("test.").parse_domain().unwrap_err(),
-                ParseError("empty label in domain", 5)
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in domain",
+                    5..5,
+                    &[Token::Atext]
+                )
             )
         }
     }
 
     #[cfg(feature = "literals")]
     mod literals {
-        use super::super::{ParseError, Parser};
+        use super::super::{ParseError, ParseErrorKind, Parser, Token};
 
         #[test]
         fn test_parse_literal_domain() {
             assert_eq!(
                 Parser::new("[test]").parse_domain().unwrap(),
-                ("test".to_string(), true)
+                ("test".into(), true)
             )
         }
 
+        #[test]
+        fn test_parse_literal_domain_canonicalizes_ip_addresses() {
+            assert_eq!(
+                Parser::new("[192.000.2.01]").parse_domain().unwrap(),
+                ("192.0.2.1".into(), true)
+            );
+            assert_eq!(
+                Parser::new("[ipv6:2001:DB8:0::1]").parse_domain().unwrap(),
+                ("IPv6:2001:db8::1".into(), true)
+            );
+        }
+
+        #[test]
+        fn test_parse_literal_domain_with_invalid_ip_address() {
+            assert_eq!(
+                Parser::new("[IPv6:::::]").parse_domain().unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::InvalidAddressLiteral,
+                    "invalid address literal",
+                    1..10,
+                    &[Token::AddressLiteral]
+                )
+            );
+            assert_eq!(
+                Parser::new("[192.0.2.256]")
+                    .parse_domain()
+                    .unwrap_err()
+                    .kind(),
+                ParseErrorKind::InvalidAddressLiteral
+            );
+        }
+
         #[test]
         fn test_parse_literal_domain_without_bracket() {
             assert_eq!(
                 Parser::new("[test").parse_domain().unwrap_err(),
-                ParseError("expected ']' for domain literal", 5)
+                ParseError::new(
+                    ParseErrorKind::UnterminatedDomainLiteral,
+                    "expected ']' for domain literal",
+                    5..5,
+                    &[Token::Dtext, Token::Char(']')]
+                )
             )
         }
 
         #[test]
         fn test_parse_empty_literal_domain() {
-            assert_eq!(
-                Parser::new("[]").parse_domain().unwrap(),
-                ("".to_string(), true)
-            )
+            assert_eq!(Parser::new("[]").parse_domain().unwrap(), ("".into(), true))
         }
 
         #[test]
         fn test_parse_empty_literal_domain_without_bracket() {
             assert_eq!(
                 Parser::new("[").parse_domain().unwrap_err(),
-                ParseError("expected ']' for domain literal", 1)
+                ParseError::new(
+                    ParseErrorKind::UnterminatedDomainLiteral,
+                    "expected ']' for domain literal",
+                    1..1,
+                    &[Token::Dtext, Token::Char(']')]
+                )
             )
         }
 
@@ -495,7 +1759,12 @@ mod tests {
         fn test_parse_literal_domain_with_white_spaces() {
             assert_eq!(
                 Parser::new("[te st]").parse_domain().unwrap_err(),
-                ParseError("expected ']' for domain literal", 3)
+                ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: ' ' },
+                    "expected ']' for domain literal",
+                    3..4,
+                    &[Token::Dtext, Token::Char(']')]
+                )
             )
         }
 
@@ -504,7 +1773,7 @@ mod tests {
         fn test_parse_literal_domain_with_white_spaces() {
             assert_eq!(
                 Parser::new("[te st]").parse_domain().unwrap(),
-                ("test".to_string(), true)
+                ("test".into(), true)
             )
         }
 
@@ -513,7 +1782,7 @@ mod tests {
         fn test_parse_literal_domain_with_fws_in_front() {
             assert_eq!(
                 Parser::new("[\r\ntest]").parse_domain().unwrap(),
-                ("test".to_string(), true)
+                ("test".into(), true)
             )
         }
 
@@ -522,7 +1791,7 @@ mod tests {
         fn test_parse_literal_domain_with_fws_in_middle() {
             assert_eq!(
                 Parser::new("[te\r\nst]").parse_domain().unwrap(),
-                ("test".to_string(), true)
+                ("test".into(), true)
             )
         }
 
@@ -531,8 +1800,1037 @@ mod tests {
         fn test_parse_literal_domain_with_fws_in_back() {
             assert_eq!(
                 Parser::new("[test\r\n]").parse_domain().unwrap(),
-                ("test".to_string(), true)
+                ("test".into(), true)
+            )
+        }
+    }
+
+    mod options {
+        use super::super::{ParseError, ParseErrorKind, Parser, ParserOptions, Profile, Token};
+
+        fn strict() -> ParserOptions {
+            let mut options = ParserOptions::new();
+            options.white_spaces = false;
+            options.comments = false;
+            options.literals = false;
+            options
+        }
+
+        #[test]
+        fn test_parse_with_white_spaces_disabled() {
+            assert_eq!(
+                Parser::with_options(" jdoe@machine.example", &strict())
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in local part",
+                    0..0,
+                    &[Token::Atext]
+                )
             )
         }
+
+        #[test]
+        fn test_parse_with_white_spaces_enabled() {
+            let addr_spec =
+                Parser::with_options(" jdoe @ machine.example\r\n ", &Profile::Rfc5322.into())
+                    .parse()
+                    .unwrap();
+            assert_eq!(addr_spec.local_part(), "jdoe");
+            assert_eq!(addr_spec.domain(), "machine.example");
+        }
+
+        #[test]
+        fn test_parse_with_comments_disabled() {
+            let mut options = ParserOptions::from(Profile::Rfc5322);
+            options.comments = false;
+            assert_eq!(
+                Parser::with_options("jdoe@machine.example (John Doe)", &options)
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::TrailingInput,
+                    "expected end of address",
+                    21..31,
+                    &[Token::End]
+                )
+            )
+        }
+
+        #[test]
+        fn test_parse_with_comments_enabled() {
+            let addr_spec = Parser::with_options(
+                "(John Doe) jdoe@machine.example (John Doe)",
+                &Profile::Rfc5322.into(),
+            )
+            .parse()
+            .unwrap();
+            assert_eq!(addr_spec.local_part(), "jdoe");
+            assert_eq!(addr_spec.domain(), "machine.example");
+        }
+
+        #[test]
+        fn test_parse_with_literals_disabled() {
+            assert_eq!(
+                Parser::with_options("jdoe@[machine.example]", &strict())
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in domain",
+                    5..5,
+                    &[Token::Atext]
+                )
+            )
+        }
+
+        #[test]
+        fn test_parse_with_literals_enabled() {
+            let addr_spec =
+                Parser::with_options("jdoe@[machine.example]", &Profile::Rfc5322.into())
+                    .parse()
+                    .unwrap();
+            assert_eq!(addr_spec.domain(), "machine.example");
+            assert!(addr_spec.is_literal());
+        }
+
+        #[test]
+        fn test_parse_with_normalization_disabled() {
+            let mut options = ParserOptions::new();
+            options.normalization = false;
+            let addr_spec = Parser::with_options("cafe\u{301}@example.com", &options)
+                .parse()
+                .unwrap();
+            assert_eq!(addr_spec.local_part(), "cafe\u{301}");
+        }
+
+        #[cfg(feature = "normalization")]
+        #[test]
+        fn test_parse_with_normalization_enabled() {
+            let addr_spec = Parser::new("cafe\u{301}@example.com").parse().unwrap();
+            assert_eq!(addr_spec.local_part(), "caf\u{e9}");
+        }
+    }
+
+    mod obsolete {
+        use super::super::{ParseError, ParseErrorKind, Parser, ParserOptions, Token};
+
+        fn obsolete() -> ParserOptions {
+            let mut options = ParserOptions::new();
+            options.obsolete = true;
+            options
+        }
+
+        #[test]
+        fn test_parse_obs_local_part_and_domain() {
+            let addr_spec = Parser::with_options("john . doe@example . com", &obsolete())
+                .parse()
+                .unwrap();
+            assert_eq!(addr_spec.local_part(), "john.doe");
+            assert_eq!(addr_spec.domain(), "example.com");
+            assert_eq!(addr_spec.to_string(), "john.doe@example.com");
+        }
+
+        #[test]
+        fn test_parse_obs_local_part_with_quoted_word() {
+            let addr_spec = Parser::with_options("\"john\".doe@example.com", &obsolete())
+                .parse()
+                .unwrap();
+            assert_eq!(addr_spec.local_part(), "john.doe");
+            assert!(!addr_spec.is_quoted());
+            assert_eq!(addr_spec.to_string(), "john.doe@example.com");
+        }
+
+        #[test]
+        fn test_parse_obs_local_part_with_quoted_special_word() {
+            let addr_spec = Parser::with_options("john.\"@\"@example.com", &obsolete())
+                .parse()
+                .unwrap();
+            assert_eq!(addr_spec.local_part(), "john.@");
+            assert_eq!(addr_spec.to_string(), "\"john.@\"@example.com");
+        }
+
+        #[test]
+        fn test_parse_obs_fws() {
+            let addr_spec = Parser::with_options("john\r\n \r\n .doe@example.com", &obsolete())
+                .parse()
+                .unwrap();
+            assert_eq!(addr_spec.local_part(), "john.doe");
+        }
+
+        #[test]
+        fn test_parse_obs_local_part_with_empty_label() {
+            assert_eq!(
+                Parser::with_options("john..doe@example.com", &obsolete())
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in local part",
+                    5..5,
+                    &[Token::Atext]
+                )
+            );
+        }
+
+        #[test]
+        fn test_parse_obs_domain_with_empty_label() {
+            assert_eq!(
+                Parser::with_options("jdoe@example . . com", &obsolete())
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in domain",
+                    15..15,
+                    &[Token::Atext]
+                )
+            );
+        }
+
+        #[test]
+        fn test_parse_obsolete_disabled() {
+            assert_eq!(
+                Parser::new("\"john\".doe@example.com").parse().unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: '.' },
+                    "expected '@'",
+                    6..7,
+                    &[Token::Char('@')]
+                )
+            );
+        }
+
+        #[test]
+        fn test_parse_obs_phrase() {
+            let mailbox = Parser::with_options("John Q. Public <jqp@example.com>", &obsolete())
+                .parse_mailbox()
+                .unwrap();
+            assert_eq!(mailbox.display_name(), Some("John Q. Public"));
+            assert_eq!(mailbox.to_string(), "\"John Q. Public\" <jqp@example.com>");
+        }
+
+        #[test]
+        fn test_parse_obs_phrase_starting_with_period() {
+            for input in [".John <jdoe@example.com>", ". <jdoe@example.com>"] {
+                assert!(
+                    Parser::with_options(input, &obsolete())
+                        .parse_mailbox()
+                        .is_err(),
+                    "{input:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn test_parse_obs_route() {
+            let mailbox = Parser::with_options(
+                "John <@a.example,,@b.example:jdoe@example.com>",
+                &obsolete(),
+            )
+            .parse_mailbox()
+            .unwrap();
+            assert_eq!(mailbox.to_string(), "John <jdoe@example.com>");
+        }
+
+        #[test]
+        fn test_parse_obs_address_list() {
+            let addresses = Parser::with_options(", a@x.com, , Team: , b@y.com, ;,", &obsolete())
+                .parse_address_list();
+            assert_eq!(
+                addresses
+                    .into_iter()
+                    .map(|address| address.unwrap().to_string())
+                    .collect::<Vec<_>>(),
+                ["a@x.com", "Team: b@y.com;"]
+            );
+        }
+
+        #[test]
+        fn test_parse_obs_address_list_without_addresses() {
+            assert_eq!(
+                Parser::with_options(" , ,", &obsolete()).parse_address_list(),
+                [Err(ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty label in local part",
+                    4..4,
+                    &[Token::Atext]
+                ))]
+            );
+        }
+    }
+
+    mod profiles {
+        use super::super::{ParseError, ParseErrorKind, Parser, ParserOptions, Profile, Token};
+
+        fn parse(input: &str, profile: Profile) -> Result<crate::AddrSpec, ParseError> {
+            Parser::with_options(input, &profile.into()).parse()
+        }
+
+        #[test]
+        fn test_parse_html5() {
+            for input in ["jdoe@example", "j.doe+x@1.example-2.com", ".a..b.@c"] {
+                assert!(parse(input, Profile::Html5).is_ok(), "{input:?}");
+            }
+            let addr_spec = parse("a..b@c", Profile::Html5).unwrap();
+            assert_eq!(addr_spec.local_part(), "a..b");
+            assert_eq!(addr_spec.to_string(), "\"a..b\"@c");
+        }
+
+        #[test]
+        fn test_parse_html5_rejects_rfc5322_forms() {
+            for (input, kind) in [
+                ("\"a\"@b", ParseErrorKind::InvalidCharacter { found: '"' }),
+                (
+                    "\u{e9}@b",
+                    ParseErrorKind::InvalidCharacter { found: '\u{e9}' },
+                ),
+                ("(a)b@c", ParseErrorKind::InvalidCharacter { found: '(' }),
+                ("a @b", ParseErrorKind::InvalidCharacter { found: ' ' }),
+                (
+                    "j\u{f6}rg@example.com",
+                    ParseErrorKind::InvalidCharacter { found: '\u{f6}' },
+                ),
+                (
+                    "a@[192.0.2.1]",
+                    ParseErrorKind::InvalidCharacter { found: '[' },
+                ),
+                ("a@b_c", ParseErrorKind::TrailingInput),
+                ("jdoe@m\u{fc}nchen.de", ParseErrorKind::TrailingInput),
+                ("a@b.", ParseErrorKind::EmptyLabel),
+                ("a@-b", ParseErrorKind::InvalidCharacter { found: '-' }),
+            ] {
+                assert_eq!(
+                    parse(input, Profile::Html5).unwrap_err().kind(),
+                    kind,
+                    "{input:?}"
+                );
+            }
+            assert_eq!(
+                parse("\"a\"@b", Profile::Html5).unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: '"' },
+                    "invalid character in local part",
+                    0..1,
+                    &[Token::Html5LocalPart]
+                )
+            );
+        }
+
+        #[test]
+        fn test_parse_html5_domain_errors() {
+            assert_eq!(
+                parse("a@b-.c", Profile::Html5).unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: '-' },
+                    "invalid hyphen in domain",
+                    3..4,
+                    &[Token::LetterOrDigit]
+                )
+            );
+            assert_eq!(
+                parse("a@b-", Profile::Html5).unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: '-' },
+                    "invalid hyphen in domain",
+                    3..4,
+                    &[Token::LetterOrDigit]
+                )
+            );
+            assert_eq!(
+                parse(&format!("a@{}.com", "b".repeat(64)), Profile::Html5).unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::LabelTooLong,
+                    "label longer than 63 octets",
+                    2..66,
+                    &[]
+                )
+            );
+            assert!(parse(&format!("a@{}.com", "b".repeat(63)), Profile::Html5).is_ok());
+        }
+
+        #[test]
+        fn test_parse_rfc5321() {
+            let addr_spec = parse("\"john doe\"@[192.0.2.1]", Profile::Rfc5321).unwrap();
+            assert_eq!(addr_spec.local_part(), "john doe");
+            assert!(addr_spec.is_literal());
+            for input in [
+                "a@[machine.example]",
+                "a@[IPv6:1:2:3:4:5:6::7]",
+                "a@exa_mple.com",
+                "(a)b@c",
+                "a @b",
+            ] {
+                assert!(parse(input, Profile::Rfc5321).is_err(), "{input:?}");
+            }
+        }
+
+        #[test]
+        fn test_parse_rfc5322() {
+            let options = ParserOptions::from_profile(Profile::Rfc5322);
+            let addr_spec = Parser::with_options("(x) \"a\" @ [b_c]", &options)
+                .parse()
+                .unwrap();
+            assert_eq!(addr_spec.to_string(), "a@[b_c]");
+        }
+    }
+
+    mod length_limits {
+        use super::super::{ParseError, ParseErrorKind, Parser, ParserOptions, Profile};
+
+        #[test]
+        fn test_length_limits_disabled() {
+            let input = format!("{}@example.com", "a".repeat(65));
+            assert!(Parser::new(&input).parse().is_ok());
+        }
+
+        #[test]
+        fn test_local_part_too_long() {
+            let mut options = ParserOptions::new();
+            options.length_limits = true;
+            let input = format!("{}@example.com", "a".repeat(64));
+            assert!(Parser::with_options(&input, &options).parse().is_ok());
+            let input = format!("{}@example.com", "a".repeat(65));
+            assert_eq!(
+                Parser::with_options(&input, &options).parse().unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::LocalPartTooLong,
+                    "local part longer than 64 octets",
+                    0..65,
+                    &[]
+                )
+            );
+            // Octets are counted after UTF-8 encoding.
+            let input = format!("{}@example.com", "\u{f6}".repeat(33));
+            assert_eq!(
+                Parser::with_options(&input, &options)
+                    .parse()
+                    .unwrap_err()
+                    .kind(),
+                ParseErrorKind::LocalPartTooLong
+            );
+            // Quotes and quoted pairs count as well.
+            let input = format!("\"{}\\\\\"@example.com", "a".repeat(61));
+            assert_eq!(
+                Parser::with_options(&input, &options)
+                    .parse()
+                    .unwrap_err()
+                    .kind(),
+                ParseErrorKind::LocalPartTooLong
+            );
+        }
+
+        #[test]
+        fn test_label_too_long() {
+            let mut options = ParserOptions::new();
+            options.length_limits = true;
+            let input = format!("jdoe@{}.com", "a".repeat(63));
+            assert!(Parser::with_options(&input, &options).parse().is_ok());
+            let input = format!("jdoe@example.{}", "a".repeat(64));
+            assert_eq!(
+                Parser::with_options(&input, &options).parse().unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::LabelTooLong,
+                    "label longer than 63 octets",
+                    13..77,
+                    &[]
+                )
+            );
+        }
+
+        #[test]
+        fn test_domain_too_long() {
+            let mut options = ParserOptions::new();
+            options.length_limits = true;
+            let input = format!("jdoe@{}.ab", vec!["a".repeat(63); 4].join("."));
+            assert_eq!(
+                Parser::with_options(&input, &options).parse().unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::DomainTooLong,
+                    "domain longer than 255 octets",
+                    5..263,
+                    &[]
+                )
+            );
+        }
+
+        #[test]
+        fn test_address_too_long() {
+            let mut options = ParserOptions::new();
+            options.length_limits = true;
+            let domain = format!("{0}.{0}.{0}.{1}", "a".repeat(63), "a".repeat(54));
+            let input = format!("{}@{domain}", "j".repeat(7));
+            assert!(Parser::with_options(&input, &options).parse().is_ok());
+            let input = format!("{}@{domain}", "j".repeat(8));
+            assert_eq!(
+                Parser::with_options(&input, &options).parse().unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::AddressTooLong,
+                    "address longer than 254 octets",
+                    0..255,
+                    &[]
+                )
+            );
+        }
+
+        #[test]
+        fn test_length_limits_of_rfc5321_profile() {
+            let input = format!("{}@example.com", "a".repeat(65));
+            assert_eq!(
+                Parser::with_options(&input, &Profile::Rfc5321.into())
+                    .parse()
+                    .unwrap_err()
+                    .kind(),
+                ParseErrorKind::LocalPartTooLong
+            );
+        }
+    }
+
+    mod hostnames {
+        use super::super::{ParseError, ParseErrorKind, Parser, ParserOptions, Token};
+        use crate::{AddrSpec, PrefixCheck};
+
+        #[test]
+        fn test_valid_hostnames() {
+            let mut options = ParserOptions::new();
+            options.hostname = true;
+            for input in [
+                "jdoe@example.com",
+                "jdoe@ex-am-ple.com",
+                "jdoe@123.example",
+                "jdoe@localhost",
+                "jdoe@xn--mnchen-3ya.de",
+                "jdoe@m\u{fc}nchen.de",
+            ] {
+                assert!(
+                    Parser::with_options(input, &options).parse().is_ok(),
+                    "{input:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn test_invalid_character_in_hostname() {
+            let mut options = ParserOptions::new();
+            options.hostname = true;
+            assert_eq!(
+                Parser::with_options("jdoe@exa_mple.com", &options)
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: '_' },
+                    "invalid character in hostname",
+                    8..9,
+                    &[Token::LetterOrDigit, Token::Char('-')]
+                )
+            );
+        }
+
+        #[test]
+        fn test_invalid_hyphen_in_hostname() {
+            let mut options = ParserOptions::new();
+            options.hostname = true;
+            assert_eq!(
+                Parser::with_options("jdoe@-foo.com", &options)
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: '-' },
+                    "invalid hyphen in hostname",
+                    5..6,
+                    &[Token::LetterOrDigit]
+                )
+            );
+            assert_eq!(
+                Parser::with_options("jdoe@foo-.com", &options)
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: '-' },
+                    "invalid hyphen in hostname",
+                    8..9,
+                    &[Token::LetterOrDigit]
+                )
+            );
+            assert_eq!(
+                Parser::with_options("jdoe@foo-", &options)
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::InvalidCharacter { found: '-' },
+                    "invalid hyphen in hostname",
+                    8..9,
+                    &[Token::LetterOrDigit]
+                )
+            );
+        }
+
+        #[test]
+        fn test_numeric_top_level_domain() {
+            let mut options = ParserOptions::new();
+            options.hostname = true;
+            assert_eq!(
+                Parser::with_options("jdoe@123.456", &options)
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::NumericTopLevelDomain,
+                    "numeric top-level domain",
+                    9..12,
+                    &[Token::LetterOrDigit]
+                )
+            );
+        }
+
+        #[test]
+        fn test_label_too_long_in_hostname() {
+            let mut options = ParserOptions::new();
+            options.hostname = true;
+            assert_eq!(
+                Parser::with_options(&format!("jdoe@{}.com", "a".repeat(64)), &options)
+                    .parse()
+                    .unwrap_err()
+                    .kind(),
+                ParseErrorKind::LabelTooLong
+            );
+            // U-labels are measured by their A-label if possible.
+            let input = format!("jdoe@{}.com", "\u{e4}".repeat(40));
+            if cfg!(feature = "idna") {
+                assert!(Parser::with_options(&input, &options).parse().is_ok());
+            } else {
+                assert_eq!(
+                    Parser::with_options(&input, &options)
+                        .parse()
+                        .unwrap_err()
+                        .kind(),
+                    ParseErrorKind::LabelTooLong
+                );
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "idna")]
+        fn test_invalid_u_labels_in_hostname() {
+            let mut options = ParserOptions::new();
+            options.hostname = true;
+            for (input, kind, span) in [
+                (
+                    "a@\u{2603}.com",
+                    ParseErrorKind::DisallowedCodePoint { found: '\u{2603}' },
+                    2..5,
+                ),
+                (
+                    "a@a\u{200d}b.com",
+                    ParseErrorKind::ContextRule { found: '\u{200d}' },
+                    2..7,
+                ),
+                (
+                    "a@\u{ff25}\u{ff38}.com",
+                    ParseErrorKind::DisallowedCodePoint { found: '\u{ff25}' },
+                    2..5,
+                ),
+                (
+                    "a@\u{3002}com",
+                    ParseErrorKind::DisallowedCodePoint { found: '\u{3002}' },
+                    2..5,
+                ),
+            ] {
+                let error = Parser::with_options(input, &options).parse().unwrap_err();
+                assert_eq!(error.kind(), kind, "{input:?}");
+                assert_eq!(error.span(), span, "{input:?}");
+            }
+        }
+
+        #[test]
+        fn test_multiple_labels() {
+            let mut options = ParserOptions::new();
+            options.multiple_labels = true;
+            assert!(Parser::with_options("jdoe@exa_mple.com", &options)
+                .parse()
+                .is_ok());
+            assert_eq!(
+                Parser::with_options("jdoe@localhost", &options)
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::SingleLabelDomain,
+                    "expected multiple labels in domain",
+                    5..14,
+                    &[Token::Char('.')]
+                )
+            );
+        }
+
+        #[test]
+        fn test_hostname_prefixes_are_viable() {
+            let mut options = ParserOptions::new();
+            options.hostname = true;
+            options.multiple_labels = true;
+            for input in ["jdoe@example", "jdoe@example.1", "jdoe@ex-"] {
+                assert_eq!(
+                    AddrSpec::check_prefix_with(input, &options),
+                    PrefixCheck::IncompleteButViable,
+                    "{input:?}"
+                );
+            }
+            assert!(!AddrSpec::check_prefix_with("jdoe@-", &options).is_viable());
+        }
+    }
+
+    mod global_literals {
+        use super::super::{ParseError, ParseErrorKind, Parser, ParserOptions, Profile};
+        use crate::IpClass;
+
+        #[test]
+        fn test_global_literals() {
+            let mut options = ParserOptions::new();
+            options.literals = true;
+            options.global_literals = true;
+            for input in [
+                "jdoe@[8.8.8.8]",
+                "jdoe@[IPv6:2606:4700::1111]",
+                "jdoe@[192.0.0.9]",
+                "jdoe@[IPv6:2001:4:112::1]",
+                "jdoe@[tag:content]",
+                "jdoe@example.com",
+            ] {
+                assert!(
+                    Parser::with_options(input, &options).parse().is_ok(),
+                    "{input:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn test_restricted_literals() {
+            let mut options = ParserOptions::new();
+            options.literals = true;
+            options.global_literals = true;
+            for (input, class) in [
+                ("jdoe@[127.0.0.1]", IpClass::Loopback),
+                ("jdoe@[10.1.2.3]", IpClass::Private),
+                ("jdoe@[169.254.169.254]", IpClass::LinkLocal),
+                ("jdoe@[224.0.0.1]", IpClass::Multicast),
+                ("jdoe@[192.0.2.1]", IpClass::Documentation),
+                ("jdoe@[IPv6:::1]", IpClass::Loopback),
+                ("jdoe@[IPv6:::ffff:192.168.0.1]", IpClass::Private),
+                ("jdoe@[IPv6:64:ff9b:1::7f00:1]", IpClass::Private),
+            ] {
+                assert_eq!(
+                    Parser::with_options(input, &options)
+                        .parse()
+                        .unwrap_err()
+                        .kind(),
+                    ParseErrorKind::RestrictedAddressLiteral { class },
+                    "{input:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn test_restricted_literal_span() {
+            let mut options = ParserOptions::new();
+            options.literals = true;
+            options.global_literals = true;
+            assert_eq!(
+                Parser::with_options("jdoe@[127.0.0.1]", &options)
+                    .parse()
+                    .unwrap_err(),
+                ParseError::new(
+                    ParseErrorKind::RestrictedAddressLiteral {
+                        class: IpClass::Loopback
+                    },
+                    "address literal is not globally reachable",
+                    5..16,
+                    &[]
+                )
+            );
+        }
+
+        #[test]
+        fn test_restricted_literals_in_smtp() {
+            let mut options = ParserOptions::from_profile(Profile::Rfc5321);
+            options.global_literals = true;
+            assert!(Parser::with_options("jdoe@[127.0.0.1]", &options)
+                .parse()
+                .is_err());
+            assert!(Parser::with_options("jdoe@[8.8.8.8]", &options)
+                .parse()
+                .is_ok());
+        }
+    }
+
+    mod default_ignorables {
+        use super::super::{ParseError, ParseErrorKind, Parser, ParserOptions};
+
+        #[test]
+        fn test_default_ignorables_allowed_by_default() {
+            assert!(Parser::new("j\u{200d}doe@example.com").parse().is_ok());
+        }
+
+        #[test]
+        fn test_default_ignorables_rejected() {
+            let mut options = ParserOptions::new();
+            options.default_ignorables = false;
+            for (input, found, span) in [
+                ("j\u{200d}doe@example.com", '\u{200d}', 1..4),
+                ("\"j\u{200c}doe\"@example.com", '\u{200c}', 2..5),
+                ("jdoe@exam\u{ad}ple.com", '\u{ad}', 9..11),
+                ("jdoe@\u{202e}example.com", '\u{202e}', 5..8),
+            ] {
+                assert_eq!(
+                    Parser::with_options(input, &options).parse().unwrap_err(),
+                    ParseError::new(
+                        ParseErrorKind::InvalidCharacter { found },
+                        "default-ignorable character",
+                        span,
+                        &[]
+                    ),
+                    "{input:?}"
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "security")]
+    mod restriction_levels {
+        use super::super::{ParseError, ParseErrorKind, Parser, ParserOptions};
+        use crate::RestrictionLevel;
+
+        #[test]
+        fn test_restriction_level_satisfied() {
+            let mut options = ParserOptions::new();
+            options.restriction_level = Some(RestrictionLevel::HighlyRestrictive);
+            for input in [
+                "jdoe@example.com",
+                "j\u{f6}rg+tag@m\u{fc}nchen.de",
+                "\u{3b9}\u{3c9}\u{3b1}\u{3bd}\u{3bd}\u{3b7}\u{3c2}@example.com",
+                "jdoe@\u{43f}\u{440}\u{438}\u{43c}\u{435}\u{440}.com",
+            ] {
+                assert!(
+                    Parser::with_options(input, &options).parse().is_ok(),
+                    "{input:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn test_restriction_level_not_met() {
+            let mut options = ParserOptions::new();
+            options.restriction_level = Some(RestrictionLevel::HighlyRestrictive);
+            for (input, span) in [
+                ("p\u{430}ypal@example.com", 0..7),
+                ("jdoe@p\u{430}ypal.com", 5..12),
+                ("jdoe@example.p\u{430}ypal", 13..20),
+            ] {
+                assert_eq!(
+                    Parser::with_options(input, &options).parse().unwrap_err(),
+                    ParseError::new(
+                        ParseErrorKind::RestrictionLevelNotMet,
+                        "restriction level not met",
+                        span,
+                        &[]
+                    ),
+                    "{input:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn test_ascii_only() {
+            let mut options = ParserOptions::new();
+            options.restriction_level = Some(RestrictionLevel::AsciiOnly);
+            assert!(Parser::with_options("jdoe@example.com", &options)
+                .parse()
+                .is_ok());
+            assert!(Parser::with_options("j\u{f6}rg@example.com", &options)
+                .parse()
+                .is_err());
+        }
+    }
+
+    #[cfg(feature = "precis")]
+    mod precis {
+        use super::super::{ParseError, ParseErrorKind, Parser, ParserOptions};
+        use crate::PrecisProfile;
+
+        #[test]
+        fn test_precis_mappings() {
+            for (profile, input, local_part) in [
+                (
+                    PrecisProfile::UsernameCaseMapped,
+                    "JDoe@example.com",
+                    "jdoe",
+                ),
+                (
+                    PrecisProfile::UsernameCasePreserved,
+                    "JDoe@example.com",
+                    "JDoe",
+                ),
+                (
+                    PrecisProfile::UsernameCaseMapped,
+                    "\u{ff2a}\u{ff24}oe@example.com",
+                    "jdoe",
+                ),
+                (
+                    PrecisProfile::UsernameCaseMapped,
+                    "\"J.Doe\"@example.com",
+                    "j.doe",
+                ),
+                (
+                    PrecisProfile::UsernameCaseMapped,
+                    "J\u{d6}RG@example.com",
+                    "j\u{f6}rg",
+                ),
+            ] {
+                let mut options = ParserOptions::new();
+                options.precis = Some(profile);
+                let addr_spec = Parser::with_options(input, &options).parse().unwrap();
+                assert_eq!(addr_spec.local_part(), local_part, "{input:?}");
+                assert_eq!(addr_spec.domain(), "example.com", "{input:?}");
+            }
+        }
+
+        #[test]
+        fn test_precis_errors() {
+            let mut options = ParserOptions::new();
+            options.precis = Some(PrecisProfile::UsernameCaseMapped);
+            for (input, kind, message, span) in [
+                (
+                    "\"j\u{2603}\"@example.com",
+                    ParseErrorKind::DisallowedCodePoint { found: '\u{2603}' },
+                    "disallowed code point",
+                    2..5,
+                ),
+                (
+                    "j\u{2603}@example.com",
+                    ParseErrorKind::DisallowedCodePoint { found: '\u{2603}' },
+                    "disallowed code point",
+                    1..4,
+                ),
+                (
+                    "\"j\\d\u{2603}\"@example.com",
+                    ParseErrorKind::DisallowedCodePoint { found: '\u{2603}' },
+                    "disallowed code point",
+                    0..8,
+                ),
+                (
+                    "j\u{200d}doe@example.com",
+                    ParseErrorKind::ContextRule { found: '\u{200d}' },
+                    "contextual rule not satisfied",
+                    1..4,
+                ),
+                (
+                    "\u{5d0}1a@example.com",
+                    ParseErrorKind::BidiRule,
+                    "local part does not satisfy the bidi rule",
+                    0..4,
+                ),
+            ] {
+                assert_eq!(
+                    Parser::with_options(input, &options).parse().unwrap_err(),
+                    ParseError::new(kind, message, span, &[]),
+                    "{input:?}"
+                );
+            }
+        }
+    }
+
+    mod diagnostics {
+        use super::super::{DiagnosticKind, Parser, ParserOptions, Profile};
+        use crate::Severity;
+
+        fn diagnose(address: &str, options: &ParserOptions) -> Vec<(DiagnosticKind, usize)> {
+            let (_, diagnostics) = Parser::with_options(address, options)
+                .parse_with_diagnostics()
+                .unwrap();
+            diagnostics
+                .into_iter()
+                .map(|diagnostic| (diagnostic.kind(), diagnostic.span().start))
+                .collect()
+        }
+
+        #[test]
+        fn test_parse_without_diagnostics() {
+            assert_eq!(
+                diagnose("jdoe@machine.example", &Profile::Rfc5322.into()),
+                []
+            );
+        }
+
+        #[test]
+        fn test_parse_with_suspicious_forms() {
+            assert_eq!(
+                diagnose("\"a b\"(x)@[1.2.3.4]", &Profile::Rfc5322.into()),
+                [
+                    (DiagnosticKind::QuotedLocalPart, 0),
+                    (DiagnosticKind::FoldingWhiteSpace, 2),
+                    (DiagnosticKind::Comment, 5),
+                    (DiagnosticKind::CfwsNearAt, 5),
+                    (DiagnosticKind::AddressLiteral, 9),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_parse_with_comment_containing_white_spaces() {
+            let (_, diagnostics) =
+                Parser::with_options("jdoe@machine.example (John Doe)", &Profile::Rfc5322.into())
+                    .parse_with_diagnostics()
+                    .unwrap();
+            assert_eq!(diagnostics.len(), 2);
+            assert_eq!(diagnostics[0].kind(), DiagnosticKind::FoldingWhiteSpace);
+            assert_eq!(diagnostics[0].severity(), Severity::Cfws);
+            assert_eq!(diagnostics[1].kind(), DiagnosticKind::Comment);
+            assert_eq!(diagnostics[1].span(), 21..31);
+        }
+
+        #[test]
+        fn test_parse_with_domain_literal() {
+            let (_, diagnostics) =
+                Parser::with_options("jdoe@[machine.example]", &Profile::Rfc5322.into())
+                    .parse_with_diagnostics()
+                    .unwrap();
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].kind(), DiagnosticKind::DomainLiteral);
+            assert_eq!(diagnostics[0].severity(), Severity::Rfc5322);
+            assert_eq!(diagnostics[0].span(), 5..22);
+        }
+
+        #[test]
+        fn test_parse_with_obsolete_syntax() {
+            let mut options = ParserOptions::new();
+            options.obsolete = true;
+            assert_eq!(
+                diagnose("\"john\".doe@example . com", &options),
+                [
+                    (DiagnosticKind::ObsoleteLocalPart, 0),
+                    (DiagnosticKind::ObsoleteDomain, 11),
+                    (DiagnosticKind::FoldingWhiteSpace, 18),
+                    (DiagnosticKind::FoldingWhiteSpace, 20),
+                ]
+            );
+            assert_eq!(
+                diagnose("\"john.doe\"@example.com", &options),
+                [(DiagnosticKind::QuotedLocalPart, 0)]
+            );
+        }
+
+        #[test]
+        fn test_parse_with_length_limits_exceeded() {
+            let local_part = "a".repeat(65);
+            let label = "b".repeat(64);
+            assert_eq!(
+                diagnose(
+                    &format!("{local_part}@{label}.com"),
+                    &Profile::Rfc5322.into()
+                ),
+                [
+                    (DiagnosticKind::LocalPartTooLong, 0),
+                    (DiagnosticKind::LabelTooLong, 66),
+                ]
+            );
+        }
     }
 }
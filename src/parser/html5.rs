@@ -0,0 +1,126 @@
+use std::{borrow::Cow, ops::Range};
+
+use super::{ParseError, ParseErrorKind, Parser, Token};
+use crate::AddrSpecRef;
+
+/// Returns whether the character is valid in the local part of a [valid email
+/// address](https://html.spec.whatwg.org/multipage/input.html#valid-e-mail-address).
+const fn is_html5_local_part_char(chr: char) -> bool {
+    chr.is_ascii_alphanumeric()
+        || matches!(
+            chr,
+            '.' | '!'
+                | '#'
+                | '$'
+                | '%'
+                | '&'
+                | '\''
+                | '*'
+                | '+'
+                | '/'
+                | '='
+                | '?'
+                | '^'
+                | '_'
+                | '`'
+                | '{'
+                | '|'
+                | '}'
+                | '~'
+                | '-'
+        )
+}
+
+impl<'a> Parser<'a> {
+    /// Parses a valid email address as defined by the WHATWG HTML Living
+    /// Standard and returns it together with the spans of the local part and
+    /// domain.
+    pub(super) fn parse_html5_addr_spec(
+        &mut self,
+    ) -> Result<(AddrSpecRef<'a>, Range<usize>, Range<usize>), ParseError> {
+        let local_part_start = self.position();
+        let input = self.iterator.as_str();
+        let size = input
+            .find(|chr| !is_html5_local_part_char(chr))
+            .unwrap_or(input.len());
+        if size == 0 {
+            return Err(match input.chars().next() {
+                Some(chr) => ParseError::invalid_character(
+                    chr,
+                    "invalid character in local part",
+                    local_part_start,
+                    &[Token::Html5LocalPart],
+                ),
+                None => ParseError::new(
+                    ParseErrorKind::EmptyLabel,
+                    "empty local part",
+                    local_part_start..local_part_start,
+                    &[Token::Html5LocalPart],
+                ),
+            });
+        }
+        self.iterator = input[size..].chars();
+        let local_part_end = self.position();
+
+        self.skip_at(&[Token::Html5LocalPart, Token::Char('@')])?;
+
+        let domain_start = self.position();
+        let domain = self.parse_html5_domain()?;
+        Ok((
+            AddrSpecRef {
+                local_part: Cow::Borrowed(&input[..size]),
+                domain: Cow::Borrowed(domain),
+                literal: false,
+            },
+            local_part_start..local_part_end,
+            domain_start..self.position(),
+        ))
+    }
+
+    fn parse_html5_domain(&mut self) -> Result<&'a str, ParseError> {
+        let input = self.iterator.as_str();
+        loop {
+            let start = self.position();
+            let rest = self.iterator.as_str();
+            let size = rest
+                .find(|chr: char| !(chr.is_ascii_alphanumeric() || chr == '-'))
+                .unwrap_or(rest.len());
+            let label = &rest[..size];
+            if label.is_empty() {
+                return Err(self.unexpected(
+                    "empty label in domain",
+                    &[Token::LetterOrDigit],
+                    ParseErrorKind::EmptyLabel,
+                ));
+            }
+            if label.starts_with('-') {
+                return Err(ParseError::invalid_character(
+                    '-',
+                    "invalid hyphen in domain",
+                    start,
+                    &[Token::LetterOrDigit],
+                ));
+            }
+            self.iterator = rest[size..].chars();
+            if label.ends_with('-') {
+                return Err(ParseError::invalid_character(
+                    '-',
+                    "invalid hyphen in domain",
+                    start + size - 1,
+                    &[Token::LetterOrDigit],
+                ));
+            }
+            if size > 63 {
+                return Err(ParseError::new(
+                    ParseErrorKind::LabelTooLong,
+                    "label longer than 63 octets",
+                    start..start + size,
+                    &[],
+                ));
+            }
+            if !self.eat_chr('.') {
+                return Ok(&input[..input.len() - self.iterator.as_str().len()]);
+            }
+        }
+    }
+}
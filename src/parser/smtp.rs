@@ -0,0 +1,372 @@
+use std::{borrow::Cow, ops::Range};
+
+use super::{canonical_literal, is_not_dtext, ParseError, ParseErrorKind, Parser, Token};
+use crate::{
+    AddrSpec, AddrSpecRef, DomainLiteral, EsmtpParameter, MailCommand, RcptCommand, SmtpPath,
+};
+
+/// Returns whether the character is valid in a sub-domain as defined in [RFC
+/// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.2), including the
+/// U-labels of [RFC 6531](https://www.rfc-editor.org/rfc/rfc6531#section-3.3).
+pub const fn is_ldh_or_non_ascii(chr: char) -> bool {
+    chr.is_ascii_alphanumeric() || chr == '-' || !chr.is_ascii()
+}
+
+/// Returns whether the character is valid in a quoted string as defined in
+/// [RFC 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.2).
+const fn is_qtext_smtp(chr: char) -> bool {
+    matches!(chr, ' '..='!' | '#'..='[' | ']'..='~') || !chr.is_ascii()
+}
+
+/// Returns whether the literal is an address literal as defined in [RFC
+/// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.3).
+pub fn is_address_literal(literal: &str) -> bool {
+    DomainLiteral::classify(literal).is_some_and(|literal| literal.is_address_literal())
+}
+
+fn is_ldh_str(value: &str) -> bool {
+    !value.is_empty()
+        && !value.starts_with('-')
+        && !value.ends_with('-')
+        && value.chars().all(is_ldh_or_non_ascii)
+}
+
+/// Returns whether the character is valid in an ESMTP value as defined in [RFC
+/// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.2), including
+/// the UTF-8 extension of [RFC
+/// 6531](https://www.rfc-editor.org/rfc/rfc6531#section-3.3).
+const fn is_esmtp_value(chr: char) -> bool {
+    matches!(chr, '!'..='<' | '>'..='~') || !chr.is_ascii()
+}
+
+impl<'a> Parser<'a> {
+    pub fn parse_mail_command(mut self) -> Result<MailCommand, ParseError> {
+        if !self.eat_str_ignore_ascii_case("MAIL FROM:") {
+            return Err(self.unexpected(
+                "expected 'MAIL FROM:'",
+                &[Token::Keyword("MAIL FROM:")],
+                ParseErrorKind::UnexpectedEnd,
+            ));
+        }
+        let start = self.position();
+        let reverse_path = self.parse_reverse_path_impl()?;
+        let parameters = self.parse_esmtp_parameters()?;
+        self.eat_str("\r\n");
+
+        if !parameters
+            .iter()
+            .any(|parameter| parameter.keyword.eq_ignore_ascii_case("SMTPUTF8"))
+        {
+            self.check_ascii(start)?;
+        }
+        self.check_end("expected end of command")?;
+        Ok(MailCommand {
+            reverse_path,
+            parameters,
+        })
+    }
+
+    /// Parses a `RCPT TO` command. Since the `SMTPUTF8` parameter is given in
+    /// the `MAIL FROM` command, the caller has to pass whether it was present.
+    pub fn parse_rcpt_command(mut self, smtputf8: bool) -> Result<RcptCommand, ParseError> {
+        if !self.eat_str_ignore_ascii_case("RCPT TO:") {
+            return Err(self.unexpected(
+                "expected 'RCPT TO:'",
+                &[Token::Keyword("RCPT TO:")],
+                ParseErrorKind::UnexpectedEnd,
+            ));
+        }
+        let start = self.position();
+        let forward_path = self.parse_forward_path_impl()?;
+        let parameters = self.parse_esmtp_parameters()?;
+        self.eat_str("\r\n");
+
+        if !smtputf8 {
+            self.check_ascii(start)?;
+        }
+        self.check_end("expected end of command")?;
+        Ok(RcptCommand {
+            forward_path,
+            parameters,
+        })
+    }
+
+    fn parse_esmtp_parameters(&mut self) -> Result<Vec<EsmtpParameter>, ParseError> {
+        let mut parameters = Vec::new();
+        while self.eat_chr(' ') {
+            let input = self.iterator.as_str();
+            let size = input
+                .find(|chr: char| !(chr.is_ascii_alphanumeric() || chr == '-'))
+                .unwrap_or(input.len());
+            if size == 0 || input.starts_with('-') {
+                return Err(self.unexpected(
+                    "expected ESMTP keyword",
+                    &[Token::EsmtpKeyword],
+                    ParseErrorKind::UnexpectedEnd,
+                ));
+            }
+            let keyword = &input[..size];
+            self.iterator = input[size..].chars();
+
+            let value = if self.eat_chr('=') {
+                let input = self.iterator.as_str();
+                let size = input
+                    .find(|chr| !is_esmtp_value(chr))
+                    .unwrap_or(input.len());
+                if size == 0 {
+                    return Err(self.unexpected(
+                        "expected ESMTP value",
+                        &[Token::EsmtpValue],
+                        ParseErrorKind::UnexpectedEnd,
+                    ));
+                }
+                self.iterator = input[size..].chars();
+                Some(input[..size].to_owned())
+            } else {
+                None
+            };
+
+            parameters.push(EsmtpParameter {
+                keyword: keyword.to_owned(),
+                value,
+            });
+        }
+        Ok(parameters)
+    }
+
+    fn eat_str_ignore_ascii_case(&mut self, pattern: &str) -> bool {
+        let input = self.iterator.as_str();
+        if input
+            .get(..pattern.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(pattern))
+        {
+            self.iterator = input[pattern.len()..].chars();
+            return true;
+        }
+        false
+    }
+
+    /// Checks that the input after `start` is ASCII, i.e. that it does not need
+    /// the `SMTPUTF8` extension.
+    fn check_ascii(&self, start: usize) -> Result<(), ParseError> {
+        match self.input[start..]
+            .char_indices()
+            .find(|(_, chr)| !chr.is_ascii())
+        {
+            Some((index, chr)) => Err(ParseError::new(
+                ParseErrorKind::SmtpUtf8Required,
+                "non-ASCII character requires SMTPUTF8",
+                start + index..start + index + chr.len_utf8(),
+                &[],
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn parse_reverse_path(mut self) -> Result<SmtpPath, ParseError> {
+        let path = self.parse_reverse_path_impl()?;
+        self.check_end("expected end of path")?;
+        Ok(path)
+    }
+
+    pub fn parse_forward_path(mut self) -> Result<SmtpPath, ParseError> {
+        let path = self.parse_forward_path_impl()?;
+        self.check_end("expected end of path")?;
+        Ok(path)
+    }
+
+    fn parse_reverse_path_impl(&mut self) -> Result<SmtpPath, ParseError> {
+        if self.eat_str("<>") {
+            return Ok(SmtpPath::Null);
+        }
+        self.parse_smtp_path()
+    }
+
+    fn parse_forward_path_impl(&mut self) -> Result<SmtpPath, ParseError> {
+        if self.eat_str_ignore_ascii_case("<Postmaster>") {
+            return Ok(SmtpPath::Postmaster);
+        }
+        self.parse_smtp_path()
+    }
+
+    fn parse_smtp_path(&mut self) -> Result<SmtpPath, ParseError> {
+        if !self.eat_chr('<') {
+            return Err(self.unexpected(
+                "expected '<' for path",
+                &[Token::Char('<')],
+                ParseErrorKind::UnexpectedEnd,
+            ));
+        }
+
+        let mut source_route = Vec::new();
+        if self.iterator.as_str().starts_with('@') {
+            loop {
+                self.eat_chr('@');
+                source_route.push(self.parse_smtp_domain()?.to_owned());
+                if self.eat_chr(':') {
+                    break;
+                }
+                if !self.iterator.as_str().starts_with(",@") {
+                    return Err(self.unexpected(
+                        "expected ':' for source route",
+                        &[Token::Char(':'), Token::Char(',')],
+                        ParseErrorKind::UnexpectedEnd,
+                    ));
+                }
+                self.eat_chr(',');
+            }
+        }
+
+        let addr_spec = self.parse_smtp_mailbox()?;
+        if !self.eat_chr('>') {
+            return Err(self.unexpected(
+                "expected '>' for path",
+                &[Token::Char('>')],
+                ParseErrorKind::UnterminatedAngleAddress,
+            ));
+        }
+        Ok(SmtpPath::Mailbox {
+            source_route,
+            addr_spec,
+        })
+    }
+
+    fn parse_smtp_mailbox(&mut self) -> Result<AddrSpec, ParseError> {
+        Ok(self.parse_smtp_mailbox_with_spans()?.0.into_owned())
+    }
+
+    /// Parses a mailbox and returns it together with the spans of the local
+    /// part and domain.
+    pub(super) fn parse_smtp_mailbox_with_spans(
+        &mut self,
+    ) -> Result<(AddrSpecRef<'a>, Range<usize>, Range<usize>), ParseError> {
+        let local_part_start = self.position();
+        let (local_part, expected): (_, &'static [Token]) = if self.eat_chr('"') {
+            (
+                Cow::Owned(self.parse_smtp_quoted_string()?),
+                &[Token::Char('@')],
+            )
+        } else {
+            (
+                Cow::Borrowed(self.parse_dot_atom("empty label in local part")?),
+                &[Token::Atext, Token::Char('.'), Token::Char('@')],
+            )
+        };
+        let local_part_end = self.position();
+
+        if !self.eat_chr('@') {
+            return Err(self.unexpected("expected '@'", expected, ParseErrorKind::MissingAt));
+        }
+
+        let domain_start = self.position();
+        let (domain, literal) = if self.eat_chr('[') {
+            let start = self.position();
+            let input = self.iterator.as_str();
+            let size = input.find(is_not_dtext).unwrap_or(input.len());
+            self.iterator = input[size..].chars();
+            if !self.eat_chr(']') {
+                return Err(self.unexpected(
+                    "expected ']' for address literal",
+                    &[Token::Dtext, Token::Char(']')],
+                    ParseErrorKind::UnterminatedDomainLiteral,
+                ));
+            }
+            if !is_address_literal(&input[..size]) {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidAddressLiteral,
+                    "invalid address literal",
+                    start..start + size,
+                    &[Token::AddressLiteral],
+                ));
+            }
+            (
+                canonical_literal(Cow::Borrowed(&input[..size])).unwrap(),
+                true,
+            )
+        } else {
+            (Cow::Borrowed(self.parse_smtp_domain()?), false)
+        };
+
+        Ok((
+            AddrSpecRef {
+                local_part: self.normalize(local_part),
+                domain: self.normalize(domain),
+                literal,
+            },
+            local_part_start..local_part_end,
+            domain_start..self.position(),
+        ))
+    }
+
+    fn parse_smtp_quoted_string(&mut self) -> Result<String, ParseError> {
+        let mut quoted_string = String::new();
+        while let Some(chr) = self.iterator.next() {
+            let chr = match chr {
+                '"' => return Ok(quoted_string),
+                '\\' => match self.iterator.next() {
+                    Some(chr @ ' '..='~') => chr,
+                    Some(chr) => {
+                        return Err(self.invalid_character(
+                            chr,
+                            "invalid character in quoted pair",
+                            &[Token::Vchar],
+                        ))
+                    }
+                    None => {
+                        return Err(self.unexpected(
+                            "unexpected end of quoted pair",
+                            &[Token::Vchar],
+                            ParseErrorKind::UnexpectedEnd,
+                        ))
+                    }
+                },
+                chr if is_qtext_smtp(chr) => chr,
+                chr => {
+                    return Err(self.invalid_character(
+                        chr,
+                        "invalid character in quoted local part",
+                        &[Token::Qtext, Token::Char('"')],
+                    ))
+                }
+            };
+            quoted_string.push(chr);
+        }
+        Err(self.unexpected(
+            "expected '\"' for quoted local part",
+            &[Token::Char('"')],
+            ParseErrorKind::UnterminatedQuote,
+        ))
+    }
+
+    fn parse_smtp_domain(&mut self) -> Result<&'a str, ParseError> {
+        let input = self.iterator.as_str();
+        let size = input
+            .find(|chr| !(is_ldh_or_non_ascii(chr) || chr == '.'))
+            .unwrap_or(input.len());
+
+        let domain = &input[..size];
+        let mut offset = 0;
+        for label in domain.split('.') {
+            if label.is_empty() {
+                return Err(self.empty_label("empty label in domain", offset));
+            }
+            if !is_ldh_str(label) {
+                let index = if label.starts_with('-') {
+                    0
+                } else {
+                    label.len() - 1
+                };
+                return Err(ParseError::invalid_character(
+                    '-',
+                    "invalid hyphen in domain",
+                    self.position() + offset + index,
+                    &[Token::LetterOrDigit],
+                ));
+            }
+            offset += label.len() + 1;
+        }
+
+        self.iterator = input[size..].chars();
+        Ok(domain)
+    }
+}
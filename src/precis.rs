@@ -0,0 +1,186 @@
+use std::{borrow::Cow, ops::Range};
+
+use precis_profiles::{
+    precis_core::{profile::Profile, CodepointInfo, DerivedPropertyValue, Error, UnexpectedError},
+    UsernameCaseMapped, UsernameCasePreserved,
+};
+
+use super::{ParseError, ParseErrorKind};
+
+/// A PRECIS profile for usernames as defined in [RFC
+/// 8265](https://www.rfc-editor.org/rfc/rfc8265#section-3), which can be
+/// enforced on local parts instead of plain NFC normalization (see
+/// [`ParserOptions::precis`](crate::ParserOptions::precis)).
+///
+/// Both profiles map full-width and half-width characters to their decomposed
+/// forms, normalize to NFC, reject characters that are not allowed in the
+/// `IdentifierClass` of [RFC 8264](https://www.rfc-editor.org/rfc/rfc8264)
+/// (e.g. spaces and symbols) and require the Bidi rule of [RFC
+/// 5893](https://www.rfc-editor.org/rfc/rfc5893#section-2) to be satisfied
+/// by right-to-left local parts.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{AddrSpec, ParserOptions, PrecisProfile};
+///
+/// let mut options = ParserOptions::new();
+/// options.precis = Some(PrecisProfile::UsernameCaseMapped);
+/// let addr_spec = AddrSpec::parse_with("\u{ff2a}Doe@example.com", &options).unwrap();
+/// assert_eq!(addr_spec.local_part(), "jdoe");
+///
+/// options.precis = Some(PrecisProfile::UsernameCasePreserved);
+/// let addr_spec = AddrSpec::parse_with("\u{ff2a}Doe@example.com", &options).unwrap();
+/// assert_eq!(addr_spec.local_part(), "JDoe");
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum PrecisProfile {
+    /// The `UsernameCaseMapped` profile, which additionally maps the local
+    /// part to lowercase.
+    UsernameCaseMapped,
+    /// The `UsernameCasePreserved` profile, which preserves the case of the
+    /// local part.
+    UsernameCasePreserved,
+}
+
+impl PrecisProfile {
+    /// Enforces the profile on the local part. The spans of errors are
+    /// relative to the local part.
+    pub(crate) fn enforce<'a>(self, local_part: Cow<'a, str>) -> Result<Cow<'a, str>, ParseError> {
+        let len = local_part.len();
+        let result = match self {
+            Self::UsernameCaseMapped => UsernameCaseMapped::new().enforce(local_part.clone()),
+            Self::UsernameCasePreserved => UsernameCasePreserved::new().enforce(local_part.clone()),
+        };
+        result.map_err(|error| match error {
+            Error::Invalid if len == 0 => {
+                ParseError::new(ParseErrorKind::EmptyLabel, "empty local part", 0..0, &[])
+            }
+            Error::Invalid => ParseError::new(
+                ParseErrorKind::BidiRule,
+                "local part does not satisfy the bidi rule",
+                0..len,
+                &[],
+            ),
+            Error::BadCodepoint(info) => {
+                let (kind, message) = match info.property {
+                    DerivedPropertyValue::ContextJ | DerivedPropertyValue::ContextO => (
+                        ParseErrorKind::ContextRule {
+                            found: found(&info),
+                        },
+                        "contextual rule not satisfied",
+                    ),
+                    _ => (
+                        ParseErrorKind::DisallowedCodePoint {
+                            found: found(&info),
+                        },
+                        "disallowed code point",
+                    ),
+                };
+                ParseError::new(kind, message, span(&local_part, &info), &[])
+            }
+            Error::Unexpected(
+                UnexpectedError::ContextRuleNotApplicable(info)
+                | UnexpectedError::MissingContextRule(info),
+            ) => ParseError::new(
+                ParseErrorKind::ContextRule {
+                    found: found(&info),
+                },
+                "contextual rule not satisfied",
+                span(&local_part, &info),
+                &[],
+            ),
+            Error::Unexpected(_) => ParseError::new(
+                ParseErrorKind::DisallowedCodePoint {
+                    found: char::REPLACEMENT_CHARACTER,
+                },
+                "disallowed code point",
+                0..len,
+                &[],
+            ),
+        })
+    }
+}
+
+fn found(info: &CodepointInfo) -> char {
+    char::from_u32(info.cp).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+/// Returns the span of the code point in the local part. Positions are
+/// counted in characters, which width mapping preserves.
+fn span(local_part: &str, info: &CodepointInfo) -> Range<usize> {
+    local_part
+        .char_indices()
+        .nth(info.position)
+        .map_or(0..local_part.len(), |(index, chr)| {
+            index..index + chr.len_utf8()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enforce(profile: PrecisProfile, local_part: &str) -> Result<Cow<'_, str>, ParseError> {
+        profile.enforce(Cow::Borrowed(local_part))
+    }
+
+    #[test]
+    fn test_mappings() {
+        for (profile, local_part, expected) in [
+            (PrecisProfile::UsernameCaseMapped, "JDoe", "jdoe"),
+            (PrecisProfile::UsernameCasePreserved, "JDoe", "JDoe"),
+            (PrecisProfile::UsernameCaseMapped, "j.doe+tag", "j.doe+tag"),
+            (
+                PrecisProfile::UsernameCaseMapped,
+                "\u{ff2a}\u{ff24}oe",
+                "jdoe",
+            ),
+            (
+                PrecisProfile::UsernameCasePreserved,
+                "\u{ff2a}\u{ff24}oe",
+                "JDoe",
+            ),
+            (PrecisProfile::UsernameCaseMapped, "J\u{d6}RG", "j\u{f6}rg"),
+            (
+                PrecisProfile::UsernameCaseMapped,
+                "jo\u{308}rg",
+                "j\u{f6}rg",
+            ),
+        ] {
+            assert_eq!(
+                enforce(profile, local_part).unwrap(),
+                expected,
+                "{local_part:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        for (local_part, kind, span) in [
+            ("", ParseErrorKind::EmptyLabel, 0..0),
+            (
+                "j doe",
+                ParseErrorKind::DisallowedCodePoint { found: ' ' },
+                1..2,
+            ),
+            (
+                "\u{e9}\u{2603}",
+                ParseErrorKind::DisallowedCodePoint { found: '\u{2603}' },
+                2..5,
+            ),
+            (
+                "j\u{200d}doe",
+                ParseErrorKind::ContextRule { found: '\u{200d}' },
+                1..4,
+            ),
+            ("\u{5d0}1a", ParseErrorKind::BidiRule, 0..4),
+        ] {
+            let error = enforce(PrecisProfile::UsernameCaseMapped, local_part).unwrap_err();
+            assert_eq!(error.kind(), kind, "{local_part:?}");
+            assert_eq!(error.span(), span, "{local_part:?}");
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use super::{AddrSpec, DomainLiteral, ParseError, ParseErrorKind, ParserOptions, Profile};
+
+/// The result of checking whether an input is, or can still become, a valid
+/// address specification.
+///
+/// This is useful to validate input while it is being typed, e.g. to only
+/// show errors that cannot be fixed by typing further.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{AddrSpec, PrefixCheck};
+///
+/// assert!(matches!(AddrSpec::check_prefix("jdoe@example.com"), PrefixCheck::Complete(_)));
+/// assert_eq!(AddrSpec::check_prefix("jdoe@"), PrefixCheck::IncompleteButViable);
+/// assert_eq!(AddrSpec::check_prefix("\"jdoe"), PrefixCheck::IncompleteButViable);
+/// assert!(matches!(AddrSpec::check_prefix("jd.."), PrefixCheck::Invalid(_)));
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PrefixCheck {
+    /// The input is a valid address specification.
+    Complete(AddrSpec),
+    /// The input is not a valid address specification, but appending more
+    /// input can make it one.
+    IncompleteButViable,
+    /// The input cannot become a valid address specification by appending
+    /// more input.
+    Invalid(ParseError),
+}
+
+impl PrefixCheck {
+    /// Returns whether the input is valid or can still become valid.
+    #[inline]
+    pub fn is_viable(&self) -> bool {
+        !matches!(self, Self::Invalid(_))
+    }
+
+    /// Returns the address specification if the input is complete.
+    #[inline]
+    pub fn complete(self) -> Option<AddrSpec> {
+        match self {
+            Self::Complete(addr_spec) => Some(addr_spec),
+            _ => None,
+        }
+    }
+}
+
+/// Returns whether the error was caused by running out of input rather than by
+/// invalid input.
+pub(crate) fn is_truncated(error: &ParseError, input: &str, options: &ParserOptions) -> bool {
+    let len = input.len();
+    // A domain literal may only be continued if its content can still become
+    // valid. Folding white spaces are not part of the content.
+    if error.index() == len && error.kind() == ParseErrorKind::UnterminatedDomainLiteral {
+        return input.rfind('[').is_some_and(|start| {
+            let literal = input[start + 1..]
+                .chars()
+                .filter(|chr| !matches!(chr, ' ' | '\t' | '\r' | '\n'))
+                .collect::<String>();
+            DomainLiteral::is_viable_prefix(&literal, options.profile == Profile::Rfc5321)
+        });
+    }
+    // The domain may still be continued with further labels.
+    if error.span().end == len
+        && matches!(
+            error.kind(),
+            ParseErrorKind::NumericTopLevelDomain | ParseErrorKind::SingleLabelDomain
+        )
+    {
+        return true;
+    }
+    // A label ending with a hyphen may still be continued with a letter or
+    // digit, unless it only consists of hyphens.
+    if error.span() == (len.saturating_sub(1)..len)
+        && error.kind() == (ParseErrorKind::InvalidCharacter { found: '-' })
+        && input
+            .trim_end_matches('-')
+            .ends_with(|chr: char| chr.is_alphanumeric())
+    {
+        return true;
+    }
+    error.index() == len
+        && matches!(
+            error.kind(),
+            ParseErrorKind::EmptyLabel
+                | ParseErrorKind::UnterminatedQuote
+                | ParseErrorKind::UnterminatedComment
+                | ParseErrorKind::MissingAt
+                | ParseErrorKind::UnexpectedEnd
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParserOptions, Profile};
+
+    fn check(input: &str) -> PrefixCheck {
+        AddrSpec::check_prefix_with(input, &Profile::Rfc5322.into())
+    }
+
+    #[test]
+    fn test_check_prefix_complete() {
+        assert_eq!(
+            check("jdoe@example.com").complete(),
+            Some(AddrSpec::new("jdoe", "example.com").unwrap())
+        );
+        assert!(matches!(
+            check("jdoe@[192.0.2.1.example]"),
+            PrefixCheck::Complete(_)
+        ));
+    }
+
+    #[test]
+    fn test_check_prefix_viable() {
+        for input in [
+            "",
+            "jdoe",
+            "jdoe.",
+            "jdoe@",
+            "jdoe@example.",
+            "\"jdoe",
+            "\"jd\\",
+            "(John",
+            "jdoe@[",
+            "jdoe@[192.0.2.",
+            // May still become a literal that is not an address literal,
+            // e.g. `[192.0.2.1.example]`.
+            "jdoe@[192.0.2.1.",
+            "jdoe@[IPv6:1:2",
+            "jdoe@[ 192.0",
+            "\"",
+            "jdoe \r",
+            "\"jdoe\r",
+        ] {
+            assert_eq!(check(input), PrefixCheck::IncompleteButViable, "{input:?}");
+        }
+
+        let options = ParserOptions::from_profile(Profile::Rfc5321);
+        for input in [
+            "jdoe@",
+            "jdoe@[",
+            "jdoe@[1.2",
+            "jdoe@[IPv6:",
+            "jdoe@[IPv6:2001:db8::",
+            "jdoe@[IPv6:::ffff:192.0.",
+            "jdoe@[x-tag:",
+        ] {
+            assert_eq!(
+                AddrSpec::check_prefix_with(input, &options),
+                PrefixCheck::IncompleteButViable,
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_prefix_with_trailing_hyphen() {
+        let options = ParserOptions::from_profile(Profile::Html5);
+        for (input, viable) in [
+            ("jdoe@ex-", true),
+            ("jdoe@ex--", true),
+            ("jdoe@-", false),
+            ("jdoe@ex.-", false),
+            ("jdoe@ex-.", false),
+        ] {
+            assert_eq!(
+                AddrSpec::check_prefix_with(input, &options).is_viable(),
+                viable,
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_prefix_invalid() {
+        for input in [
+            "jd..",
+            "jdoe@@",
+            "jdoe@example..",
+            "jdoe@example.com>",
+            "jdoe@[IPv6::::",
+            "jdoe@[IPv6:1:2:3:4:5:6:7:8:9",
+            "jdoe@[IPv6:1:2:3:4:5:6::7",
+            "jdoe@[IPv6:12345",
+            "jdoe@[IPv6:1.2",
+        ] {
+            assert!(!check(input).is_viable(), "{input:?}");
+        }
+
+        let options = ParserOptions::from_profile(Profile::Rfc5321);
+        for input in [
+            "jdoe@[1.2.3.4.",
+            "jdoe@[256.",
+            "jdoe@[1.2.x",
+            "jdoe@[x_",
+            "jdoe@[x-tag: ",
+        ] {
+            assert!(
+                !AddrSpec::check_prefix_with(input, &options).is_viable(),
+                "{input:?}"
+            );
+        }
+
+        let mut options = ParserOptions::new();
+        options.white_spaces = false;
+        assert_eq!(
+            AddrSpec::check_prefix_with("jdoe ", &options),
+            PrefixCheck::Invalid(ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: ' ' },
+                "expected '@'",
+                4..5,
+                &[
+                    crate::Token::Atext,
+                    crate::Token::Char('.'),
+                    crate::Token::Char('@')
+                ]
+            ))
+        );
+    }
+}
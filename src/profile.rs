@@ -0,0 +1,51 @@
+/// A named validation profile, i.e. the grammar used to parse an address
+/// specification.
+///
+/// Use [`ParserOptions::from_profile`] to get options that parse exactly the
+/// addresses accepted by a profile.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{AddrSpec, ParserOptions, Profile};
+///
+/// let html5 = ParserOptions::from_profile(Profile::Html5);
+/// assert!(AddrSpec::parse_with("jdoe@example.com", &html5).is_ok());
+/// assert!(AddrSpec::parse_with("\"jdoe\"@example.com", &html5).is_err());
+/// assert!(AddrSpec::parse_with("jdoe@exa_mple.com", &html5).is_err());
+///
+/// let rfc5322 = ParserOptions::from_profile(Profile::Rfc5322);
+/// assert!(AddrSpec::parse_with("\"jdoe\"@exa_mple.com", &rfc5322).is_ok());
+/// ```
+///
+/// [`ParserOptions::from_profile`]: crate::ParserOptions::from_profile
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Profile {
+    /// The `addr-spec` of [Section 3.4.1, RFC
+    /// 5322](https://www.rfc-editor.org/rfc/rfc5322#section-3.4.1) with UTF-8
+    /// support as defined in [RFC 6532](https://www.rfc-editor.org/rfc/rfc6532).
+    ///
+    /// This is the only profile affected by the `white_spaces`, `comments`,
+    /// `literals` and `obsolete` options.
+    #[default]
+    Rfc5322,
+    /// The `Mailbox` of [Section 4.1.2, RFC
+    /// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.2) with UTF-8
+    /// support as defined in [RFC 6531](https://www.rfc-editor.org/rfc/rfc6531),
+    /// i.e. an address as used in SMTP commands.
+    ///
+    /// Comments and folding white spaces are not allowed, domains consist of
+    /// LDH labels and domain literals must be address literals.
+    Rfc5321,
+    /// The [valid email
+    /// address](https://html.spec.whatwg.org/multipage/input.html#valid-e-mail-address)
+    /// of the WHATWG HTML Living Standard, i.e. what browsers accept for
+    /// `<input type=email>`.
+    ///
+    /// Addresses are ASCII-only without quoted strings, comments or domain
+    /// literals. Domains consist of LDH labels of at most 63 characters. Note
+    /// that the local part may contain arbitrary periods, e.g. `a..b`, in
+    /// which case it is serialized as a quoted string.
+    Html5,
+}
@@ -0,0 +1,138 @@
+use unicode_security::RestrictionLevelDetection;
+
+/// A restriction level as defined in [Section 5.2, UTS
+/// #39](https://www.unicode.org/reports/tr39/#Restriction_Level_Detection),
+/// ordered from most to least restrictive.
+///
+/// A string satisfies a restriction level if its level is less than or equal
+/// to it. Levels are determined separately for the local part and each label
+/// of the domain since mixing scripts across them, e.g. `ιωαννης@example.com`,
+/// is common.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::RestrictionLevel;
+///
+/// assert_eq!(RestrictionLevel::of("jdoe"), RestrictionLevel::AsciiOnly);
+/// assert_eq!(RestrictionLevel::of("\u{3b9}\u{3c9}\u{3b1}\u{3bd}\u{3bd}\u{3b7}\u{3c2}"), RestrictionLevel::SingleScript);
+/// assert_eq!(RestrictionLevel::of("p\u{430}ypal"), RestrictionLevel::MinimallyRestrictive);
+/// assert!(RestrictionLevel::of("p\u{430}ypal") > RestrictionLevel::HighlyRestrictive);
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum RestrictionLevel {
+    /// All characters are ASCII.
+    AsciiOnly,
+    /// All characters are of a single script.
+    SingleScript,
+    /// All characters are of a single script, or of one of the combinations
+    /// Latin with Han, Hiragana and Katakana; Latin with Han and Bopomofo; or
+    /// Latin with Han and Hangul.
+    HighlyRestrictive,
+    /// All characters are of Latin and a single other recommended script
+    /// except Cyrillic and Greek.
+    ModeratelyRestrictive,
+    /// All characters are allowed identifier characters, but of arbitrary
+    /// scripts.
+    MinimallyRestrictive,
+    /// Any characters.
+    Unrestricted,
+}
+
+impl RestrictionLevel {
+    /// Returns the restriction level of the value.
+    pub fn of(value: &str) -> Self {
+        use unicode_security::RestrictionLevel as Level;
+
+        match value.detect_restriction_level() {
+            Level::ASCIIOnly => Self::AsciiOnly,
+            Level::SingleScript => Self::SingleScript,
+            Level::HighlyRestrictive => Self::HighlyRestrictive,
+            Level::ModeratelyRestrictive => Self::ModeratelyRestrictive,
+            Level::MinimallyRestrictive => Self::MinimallyRestrictive,
+            Level::Unrestricted => Self::Unrestricted,
+        }
+    }
+
+    /// Returns the restriction level of a local part. ASCII punctuation, such
+    /// as the `+` of subaddresses, is ignored since it is not part of
+    /// identifiers.
+    pub(crate) fn of_local_part(local_part: &str) -> Self {
+        if local_part.is_ascii() {
+            return Self::AsciiOnly;
+        }
+        Self::of(
+            &local_part
+                .chars()
+                .filter(|chr| !(chr.is_ascii_punctuation() || *chr == ' '))
+                .collect::<String>(),
+        )
+    }
+
+    /// Returns the restriction level of a label, decoding A-labels with the
+    /// `idna` feature.
+    pub(crate) fn of_label(label: &str) -> Self {
+        #[cfg(feature = "idna")]
+        if let Some(unicode) = label
+            .get(..4)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("xn--"))
+            .and_then(|_| idna::punycode::decode_to_string(&label[4..]))
+        {
+            return Self::of(&unicode);
+        }
+        Self::of(label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restriction_levels() {
+        for (value, level) in [
+            ("jdoe", RestrictionLevel::AsciiOnly),
+            ("j\u{f6}rg", RestrictionLevel::SingleScript),
+            ("\u{4e2d}\u{6587}", RestrictionLevel::SingleScript),
+            ("abc\u{4e2d}\u{3042}", RestrictionLevel::HighlyRestrictive),
+            ("abc\u{5d0}", RestrictionLevel::ModeratelyRestrictive),
+            ("p\u{430}ypal", RestrictionLevel::MinimallyRestrictive),
+            ("\u{3b1}\u{5d0}", RestrictionLevel::MinimallyRestrictive),
+            ("j+doe", RestrictionLevel::Unrestricted),
+            ("j\u{200d}doe", RestrictionLevel::Unrestricted),
+        ] {
+            assert_eq!(RestrictionLevel::of(value), level, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn test_local_part_restriction_levels() {
+        for (local_part, level) in [
+            ("j+doe", RestrictionLevel::AsciiOnly),
+            ("j\u{f6}rg+tag", RestrictionLevel::SingleScript),
+            ("j\u{f6}rg doe", RestrictionLevel::SingleScript),
+            ("p\u{430}ypal+tag", RestrictionLevel::MinimallyRestrictive),
+            ("j\u{f6}rg\u{200d}", RestrictionLevel::Unrestricted),
+        ] {
+            assert_eq!(
+                RestrictionLevel::of_local_part(local_part),
+                level,
+                "{local_part:?}"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "idna")]
+    fn test_label_restriction_levels() {
+        assert_eq!(
+            RestrictionLevel::of_label("xn--pypal-4ve"),
+            RestrictionLevel::MinimallyRestrictive
+        );
+        assert_eq!(
+            RestrictionLevel::of_label("XN--MNCHEN-3YA"),
+            RestrictionLevel::SingleScript
+        );
+    }
+}
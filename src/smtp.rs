@@ -0,0 +1,885 @@
+#[cfg(feature = "idna")]
+use std::borrow::Cow;
+use std::fmt;
+
+#[cfg(feature = "idna")]
+use super::idn;
+use super::{parser::Parser, AddrSpec, ParseError, ParserOptions};
+
+/// Path as defined in [Section 4.1.2, RFC
+/// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.2), i.e. the
+/// argument of the SMTP `MAIL FROM` and `RCPT TO` commands.
+///
+/// The mailbox of a path follows the stricter grammar of RFC 5321 (with the
+/// UTF-8 extensions of [RFC 6531](https://www.rfc-editor.org/rfc/rfc6531)):
+/// comments, white spaces and obsolete forms are not allowed, domains must
+/// consist of letters, digits and hyphens, and domain literals must be address
+/// literals.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::SmtpPath;
+///
+/// let path = SmtpPath::parse_reverse_path("<jdoe@example.com>").unwrap();
+/// assert_eq!(path.addr_spec().unwrap().to_string(), "jdoe@example.com");
+/// assert!(path.source_route().is_empty());
+///
+/// let path = SmtpPath::parse_reverse_path("<>").unwrap();
+/// assert_eq!(path, SmtpPath::Null);
+///
+/// let path = SmtpPath::parse_forward_path("<Postmaster>").unwrap();
+/// assert_eq!(path, SmtpPath::Postmaster);
+/// ```
+///
+/// Source routes are deprecated, but are kept separately from the address
+/// specification:
+///
+/// ```
+/// use addr_spec::SmtpPath;
+///
+/// let path = SmtpPath::parse_forward_path("<@a.example,@b.example:jdoe@example.com>").unwrap();
+/// assert_eq!(path.source_route(), ["a.example", "b.example"]);
+/// assert_eq!(path.addr_spec().unwrap().to_string(), "jdoe@example.com");
+/// assert_eq!(path.to_string(), "<@a.example,@b.example:jdoe@example.com>");
+/// ```
+///
+/// # References
+///
+/// - [RFC 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.2)
+/// - [RFC 6531](https://www.rfc-editor.org/rfc/rfc6531#section-3.3)
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum SmtpPath {
+    /// The null reverse path `<>`, e.g. for delivery status notifications.
+    Null,
+    /// The special forward path `<Postmaster>` without a domain.
+    Postmaster,
+    /// A mailbox with a (possibly empty) source route.
+    Mailbox {
+        /// The domains of the source route, without the leading `@`.
+        source_route: Vec<String>,
+        /// The address specification of the mailbox.
+        addr_spec: AddrSpec,
+    },
+}
+
+impl SmtpPath {
+    /// Parses a reverse path, i.e. the argument of `MAIL FROM`. This accepts
+    /// the null path `<>`.
+    #[inline]
+    pub fn parse_reverse_path(path: &str) -> Result<Self, ParseError> {
+        Parser::with_options(path, &ParserOptions::new()).parse_reverse_path()
+    }
+
+    /// Parses a forward path, i.e. the argument of `RCPT TO`. This accepts the
+    /// special path `<Postmaster>` (case-insensitively).
+    #[inline]
+    pub fn parse_forward_path(path: &str) -> Result<Self, ParseError> {
+        Parser::with_options(path, &ParserOptions::new()).parse_forward_path()
+    }
+
+    /// Returns the address specification of the path, if any.
+    #[inline]
+    pub fn addr_spec(&self) -> Option<&AddrSpec> {
+        match self {
+            Self::Mailbox { addr_spec, .. } => Some(addr_spec),
+            _ => None,
+        }
+    }
+
+    /// Returns the domains of the source route of the path.
+    #[inline]
+    pub fn source_route(&self) -> &[String] {
+        match self {
+            Self::Mailbox { source_route, .. } => source_route,
+            _ => &[],
+        }
+    }
+
+    /// Returns the address specification of the path, if any.
+    #[inline]
+    pub fn into_addr_spec(self) -> Option<AddrSpec> {
+        match self {
+            Self::Mailbox { addr_spec, .. } => Some(addr_spec),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the path can only be transported with the `SMTPUTF8`
+    /// extension (see [`AddrSpec::requires_smtputf8`]).
+    #[inline]
+    pub fn requires_smtputf8(&self) -> bool {
+        self.addr_spec()
+            .is_some_and(|addr_spec| addr_spec.requires_smtputf8())
+    }
+
+    /// Downgrades the path for a relay without `SMTPUTF8` support as defined
+    /// in [RFC 6857](https://www.rfc-editor.org/rfc/rfc6857#section-3.1), i.e.
+    /// converts the domains of the source route and the mailbox into A-labels.
+    ///
+    /// This fails if the local part is not ASCII or a domain is not a valid
+    /// internationalized domain name (see [`AddrSpec::to_ascii`]). The spans of
+    /// errors are relative to the serialized path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{ParseErrorKind, SmtpPath};
+    ///
+    /// let path = SmtpPath::parse_forward_path("<@m\u{fc}nchen.de:jdoe@m\u{fc}nchen.de>").unwrap();
+    /// assert_eq!(
+    ///     path.downgrade().unwrap().to_string(),
+    ///     "<@xn--mnchen-3ya.de:jdoe@xn--mnchen-3ya.de>"
+    /// );
+    ///
+    /// let path = SmtpPath::parse_forward_path("<j\u{f6}rg@example.com>").unwrap();
+    /// let error = path.downgrade().unwrap_err();
+    /// assert_eq!(error.kind(), ParseErrorKind::SmtpUtf8Required);
+    /// assert_eq!(error.span(), 2..4);
+    /// ```
+    #[cfg(feature = "idna")]
+    pub fn downgrade(&self) -> Result<Self, ParseError> {
+        let Self::Mailbox {
+            source_route,
+            addr_spec,
+        } = self
+        else {
+            return Ok(self.clone());
+        };
+        // The source route starts after `<@` and each domain is followed by
+        // `,@` or `:`.
+        let mut start = "<@".len();
+        let source_route = source_route
+            .iter()
+            .map(|domain| {
+                let result = idn::domain_to_ascii(domain)
+                    .map(Cow::into_owned)
+                    .map_err(|error| error.offset(start));
+                start += domain.len() + ",@".len();
+                result
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let start = if source_route.is_empty() {
+            1
+        } else {
+            start - 1
+        };
+        let addr_spec = addr_spec.to_ascii().map_err(|error| error.offset(start))?;
+        Ok(Self::Mailbox {
+            source_route,
+            addr_spec,
+        })
+    }
+}
+
+impl From<AddrSpec> for SmtpPath {
+    #[inline]
+    fn from(addr_spec: AddrSpec) -> Self {
+        Self::Mailbox {
+            source_route: Vec::new(),
+            addr_spec,
+        }
+    }
+}
+
+impl fmt::Display for SmtpPath {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => formatter.write_str("<>"),
+            Self::Postmaster => formatter.write_str("<Postmaster>"),
+            Self::Mailbox {
+                source_route,
+                addr_spec,
+            } => {
+                formatter.write_str("<")?;
+                for (index, domain) in source_route.iter().enumerate() {
+                    formatter.write_str(if index == 0 { "@" } else { ",@" })?;
+                    formatter.write_str(domain)?;
+                }
+                if !source_route.is_empty() {
+                    formatter.write_str(":")?;
+                }
+                write!(formatter, "{addr_spec}>")
+            }
+        }
+    }
+}
+
+/// ESMTP parameter as defined in [Section 4.1.2, RFC
+/// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.2), e.g. `SIZE=1000`
+/// or `SMTPUTF8`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct EsmtpParameter {
+    pub(crate) keyword: String,
+    pub(crate) value: Option<String>,
+}
+
+impl EsmtpParameter {
+    /// Returns the keyword of the parameter as given in the command.
+    ///
+    /// Keywords are case-insensitive, so use
+    /// [`str::eq_ignore_ascii_case`] to compare them.
+    #[inline]
+    pub fn keyword(&self) -> &str {
+        &self.keyword
+    }
+
+    /// Returns the value of the parameter, if any.
+    #[inline]
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+}
+
+impl fmt::Display for EsmtpParameter {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.keyword)?;
+        if let Some(value) = &self.value {
+            write!(formatter, "={value}")?;
+        }
+        Ok(())
+    }
+}
+
+fn find_parameter<'a>(
+    parameters: &'a [EsmtpParameter],
+    keyword: &str,
+) -> Option<&'a EsmtpParameter> {
+    parameters
+        .iter()
+        .find(|parameter| parameter.keyword.eq_ignore_ascii_case(keyword))
+}
+
+fn write_parameters(
+    formatter: &mut fmt::Formatter<'_>,
+    parameters: &[EsmtpParameter],
+) -> fmt::Result {
+    for parameter in parameters {
+        write!(formatter, " {parameter}")?;
+    }
+    Ok(())
+}
+
+/// SMTP `MAIL FROM` command as defined in [Section 4.1.1.2, RFC
+/// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.1.2).
+///
+/// Non-ASCII characters are only accepted if the `SMTPUTF8` parameter of [RFC
+/// 6531](https://www.rfc-editor.org/rfc/rfc6531#section-3.4) is present.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::MailCommand;
+///
+/// let command = MailCommand::parse("MAIL FROM:<jdoe@example.com> SIZE=1000 BODY=8BITMIME\r\n").unwrap();
+/// assert_eq!(
+///     command.reverse_path().addr_spec().unwrap().to_string(),
+///     "jdoe@example.com"
+/// );
+/// assert_eq!(command.parameter("size").unwrap().value(), Some("1000"));
+/// assert!(!command.is_smtputf8());
+///
+/// assert!(MailCommand::parse("MAIL FROM:<😄@example.com>").is_err());
+/// assert!(MailCommand::parse("MAIL FROM:<😄@example.com> SMTPUTF8").is_ok());
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct MailCommand {
+    pub(crate) reverse_path: SmtpPath,
+    pub(crate) parameters: Vec<EsmtpParameter>,
+}
+
+impl MailCommand {
+    /// Parses a `MAIL FROM` command line. The trailing line break is optional.
+    #[inline]
+    pub fn parse(command: &str) -> Result<Self, ParseError> {
+        Parser::with_options(command, &ParserOptions::new()).parse_mail_command()
+    }
+
+    /// Returns the reverse path of the command.
+    #[inline]
+    pub fn reverse_path(&self) -> &SmtpPath {
+        &self.reverse_path
+    }
+
+    /// Returns the parameters of the command.
+    #[inline]
+    pub fn parameters(&self) -> &[EsmtpParameter] {
+        &self.parameters
+    }
+
+    /// Returns the parameter with the given (case-insensitive) keyword, if
+    /// any.
+    #[inline]
+    pub fn parameter(&self, keyword: &str) -> Option<&EsmtpParameter> {
+        find_parameter(&self.parameters, keyword)
+    }
+
+    /// Returns whether the `SMTPUTF8` parameter is present.
+    #[inline]
+    pub fn is_smtputf8(&self) -> bool {
+        self.parameter("SMTPUTF8").is_some()
+    }
+
+    /// Returns the reverse path and parameters of the command.
+    #[inline]
+    pub fn into_parts(self) -> (SmtpPath, Vec<EsmtpParameter>) {
+        (self.reverse_path, self.parameters)
+    }
+}
+
+impl fmt::Display for MailCommand {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "MAIL FROM:{}", self.reverse_path)?;
+        write_parameters(formatter, &self.parameters)
+    }
+}
+
+/// SMTP `RCPT TO` command as defined in [Section 4.1.1.3, RFC
+/// 5321](https://www.rfc-editor.org/rfc/rfc5321#section-4.1.1.3).
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::RcptCommand;
+///
+/// let command = RcptCommand::parse("RCPT TO:<jdoe@example.com> NOTIFY=SUCCESS,FAILURE", false).unwrap();
+/// assert_eq!(
+///     command.forward_path().addr_spec().unwrap().to_string(),
+///     "jdoe@example.com"
+/// );
+/// assert_eq!(
+///     command.parameter("NOTIFY").unwrap().value(),
+///     Some("SUCCESS,FAILURE")
+/// );
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct RcptCommand {
+    pub(crate) forward_path: SmtpPath,
+    pub(crate) parameters: Vec<EsmtpParameter>,
+}
+
+impl RcptCommand {
+    /// Parses a `RCPT TO` command line. The trailing line break is optional.
+    ///
+    /// Since `SMTPUTF8` is a parameter of the `MAIL FROM` command of the same
+    /// transaction, `smtputf8` determines whether non-ASCII characters are
+    /// accepted.
+    #[inline]
+    pub fn parse(command: &str, smtputf8: bool) -> Result<Self, ParseError> {
+        Parser::with_options(command, &ParserOptions::new()).parse_rcpt_command(smtputf8)
+    }
+
+    /// Returns the forward path of the command.
+    #[inline]
+    pub fn forward_path(&self) -> &SmtpPath {
+        &self.forward_path
+    }
+
+    /// Returns the parameters of the command.
+    #[inline]
+    pub fn parameters(&self) -> &[EsmtpParameter] {
+        &self.parameters
+    }
+
+    /// Returns the parameter with the given (case-insensitive) keyword, if
+    /// any.
+    #[inline]
+    pub fn parameter(&self, keyword: &str) -> Option<&EsmtpParameter> {
+        find_parameter(&self.parameters, keyword)
+    }
+
+    /// Returns the forward path and parameters of the command.
+    #[inline]
+    pub fn into_parts(self) -> (SmtpPath, Vec<EsmtpParameter>) {
+        (self.forward_path, self.parameters)
+    }
+}
+
+impl fmt::Display for RcptCommand {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "RCPT TO:{}", self.forward_path)?;
+        write_parameters(formatter, &self.parameters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParseErrorKind, Token};
+
+    #[test]
+    fn test_parse_reverse_path() {
+        let path = SmtpPath::parse_reverse_path("<jdoe@machine.example>").unwrap();
+        assert_eq!(
+            path,
+            SmtpPath::from(AddrSpec::new("jdoe", "machine.example").unwrap())
+        );
+        assert_eq!(path.to_string(), "<jdoe@machine.example>");
+    }
+
+    #[test]
+    fn test_parse_null_reverse_path() {
+        let path = SmtpPath::parse_reverse_path("<>").unwrap();
+        assert_eq!(path, SmtpPath::Null);
+        assert_eq!(path.addr_spec(), None);
+        assert_eq!(path.to_string(), "<>");
+    }
+
+    #[test]
+    fn test_parse_null_forward_path() {
+        assert_eq!(
+            SmtpPath::parse_forward_path("<>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::EmptyLabel,
+                "empty label in local part",
+                1..1,
+                &[Token::Atext]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_postmaster_forward_path() {
+        assert_eq!(
+            SmtpPath::parse_forward_path("<postMaster>").unwrap(),
+            SmtpPath::Postmaster
+        );
+        assert_eq!(SmtpPath::Postmaster.to_string(), "<Postmaster>");
+    }
+
+    #[test]
+    fn test_parse_postmaster_reverse_path() {
+        assert_eq!(
+            SmtpPath::parse_reverse_path("<Postmaster>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: '>' },
+                "expected '@'",
+                11..12,
+                &[Token::Atext, Token::Char('.'), Token::Char('@')]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_path_with_source_route() {
+        let path =
+            SmtpPath::parse_forward_path("<@a.example,@b.example:jdoe@machine.example>").unwrap();
+        assert_eq!(path.source_route(), ["a.example", "b.example"]);
+        assert_eq!(
+            path.addr_spec().unwrap().to_string(),
+            "jdoe@machine.example"
+        );
+        assert_eq!(
+            path.to_string(),
+            "<@a.example,@b.example:jdoe@machine.example>"
+        );
+    }
+
+    #[test]
+    fn test_parse_path_with_invalid_source_route() {
+        assert_eq!(
+            SmtpPath::parse_forward_path("<@a.example,b.example:jdoe@machine.example>")
+                .unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: ',' },
+                "expected ':' for source route",
+                11..12,
+                &[Token::Char(':'), Token::Char(',')]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_path_with_quoted_local_part() {
+        let path = SmtpPath::parse_forward_path("<\"john doe\\\"\"@machine.example>").unwrap();
+        assert_eq!(path.addr_spec().unwrap().local_part(), "john doe\"");
+        assert_eq!(path.to_string(), "<\"john\\ doe\\\"\"@machine.example>");
+    }
+
+    #[test]
+    fn test_parse_path_with_address_literals() {
+        let path = SmtpPath::parse_forward_path("<jdoe@[192.0.2.1]>").unwrap();
+        assert_eq!(path.addr_spec().unwrap().domain(), "192.0.2.1");
+        assert!(path.addr_spec().unwrap().is_literal());
+
+        let path = SmtpPath::parse_forward_path("<jdoe@[IPv6:2001:db8::1]>").unwrap();
+        assert_eq!(path.addr_spec().unwrap().domain(), "IPv6:2001:db8::1");
+
+        let path = SmtpPath::parse_forward_path("<jdoe@[x-tag:content]>").unwrap();
+        assert_eq!(path.addr_spec().unwrap().domain(), "x-tag:content");
+    }
+
+    #[test]
+    fn test_parse_path_with_invalid_address_literals() {
+        assert_eq!(
+            SmtpPath::parse_forward_path("<jdoe@[machine.example]>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidAddressLiteral,
+                "invalid address literal",
+                7..22,
+                &[Token::AddressLiteral]
+            )
+        );
+        assert_eq!(
+            SmtpPath::parse_forward_path("<jdoe@[IPv6:::::]>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidAddressLiteral,
+                "invalid address literal",
+                7..16,
+                &[Token::AddressLiteral]
+            )
+        );
+        assert_eq!(
+            SmtpPath::parse_forward_path("<jdoe@[256.0.0.1]>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidAddressLiteral,
+                "invalid address literal",
+                7..16,
+                &[Token::AddressLiteral]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_path_with_unterminated_address_literal() {
+        assert_eq!(
+            SmtpPath::parse_forward_path("<a@[1.2").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::UnterminatedDomainLiteral,
+                "expected ']' for address literal",
+                7..7,
+                &[Token::Dtext, Token::Char(']')]
+            )
+        );
+        assert_eq!(
+            SmtpPath::parse_forward_path("<a@[1.2>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::UnterminatedDomainLiteral,
+                "expected ']' for address literal",
+                8..8,
+                &[Token::Dtext, Token::Char(']')]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_path_with_unicode() {
+        let path = SmtpPath::parse_forward_path("<😄😄😄@😄😄😄.example>").unwrap();
+        assert_eq!(path.to_string(), "<😄😄😄@😄😄😄.example>");
+    }
+
+    #[test]
+    fn test_parse_path_with_cfws() {
+        assert_eq!(
+            SmtpPath::parse_forward_path("<jdoe @machine.example>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: ' ' },
+                "expected '@'",
+                5..6,
+                &[Token::Atext, Token::Char('.'), Token::Char('@')]
+            )
+        );
+        assert_eq!(
+            SmtpPath::parse_forward_path("<jdoe@machine.example(comment)>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: '(' },
+                "expected '>' for path",
+                21..22,
+                &[Token::Char('>')]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_path_with_invalid_domain() {
+        assert_eq!(
+            SmtpPath::parse_forward_path("<jdoe@-machine.example>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: '-' },
+                "invalid hyphen in domain",
+                6..7,
+                &[Token::LetterOrDigit]
+            )
+        );
+        assert_eq!(
+            SmtpPath::parse_forward_path("<jdoe@machine-.example>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: '-' },
+                "invalid hyphen in domain",
+                13..14,
+                &[Token::LetterOrDigit]
+            )
+        );
+        assert_eq!(
+            SmtpPath::parse_forward_path("<jdoe@machine..example>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::EmptyLabel,
+                "empty label in domain",
+                14..14,
+                &[Token::Atext]
+            )
+        );
+        assert_eq!(
+            SmtpPath::parse_forward_path("<jdoe@mach_ine.example>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: '_' },
+                "expected '>' for path",
+                10..11,
+                &[Token::Char('>')]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_path_without_brackets() {
+        assert_eq!(
+            SmtpPath::parse_forward_path("jdoe@machine.example").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: 'j' },
+                "expected '<' for path",
+                0..1,
+                &[Token::Char('<')]
+            )
+        );
+        assert_eq!(
+            SmtpPath::parse_forward_path("<jdoe@machine.example").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::UnterminatedAngleAddress,
+                "expected '>' for path",
+                21..21,
+                &[Token::Char('>')]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_mail_command() {
+        let command = MailCommand::parse(
+            "MAIL FROM:<jdoe@machine.example> SIZE=1000 SMTPUTF8 BODY=8BITMIME\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            command.reverse_path().addr_spec().unwrap().to_string(),
+            "jdoe@machine.example"
+        );
+        assert_eq!(
+            command
+                .parameters()
+                .iter()
+                .map(|parameter| (parameter.keyword(), parameter.value()))
+                .collect::<Vec<_>>(),
+            [
+                ("SIZE", Some("1000")),
+                ("SMTPUTF8", None),
+                ("BODY", Some("8BITMIME"))
+            ]
+        );
+        assert!(command.is_smtputf8());
+        assert_eq!(
+            command.to_string(),
+            "MAIL FROM:<jdoe@machine.example> SIZE=1000 SMTPUTF8 BODY=8BITMIME"
+        );
+    }
+
+    #[test]
+    fn test_parse_mail_command_with_null_reverse_path() {
+        let command = MailCommand::parse("mail from:<>").unwrap();
+        assert_eq!(command.reverse_path(), &SmtpPath::Null);
+        assert!(command.parameters().is_empty());
+    }
+
+    #[test]
+    fn test_parse_mail_command_with_invalid_parameters() {
+        assert_eq!(
+            MailCommand::parse("MAIL FROM:<jdoe@machine.example> SIZE=").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::UnexpectedEnd,
+                "expected ESMTP value",
+                38..38,
+                &[Token::EsmtpValue]
+            )
+        );
+        assert_eq!(
+            MailCommand::parse("MAIL FROM:<jdoe@machine.example> -SIZE").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: '-' },
+                "expected ESMTP keyword",
+                33..34,
+                &[Token::EsmtpKeyword]
+            )
+        );
+        assert_eq!(
+            MailCommand::parse("MAIL FROM:<jdoe@machine.example>  SIZE").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: ' ' },
+                "expected ESMTP keyword",
+                33..34,
+                &[Token::EsmtpKeyword]
+            )
+        );
+        assert_eq!(
+            MailCommand::parse("MAIL FROM:<jdoe@machine.example> SIZE=1=2").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::TrailingInput,
+                "expected end of command",
+                39..41,
+                &[Token::End]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_mail_command_without_colon() {
+        assert_eq!(
+            MailCommand::parse("MAIL FROM <jdoe@machine.example>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::InvalidCharacter { found: 'M' },
+                "expected 'MAIL FROM:'",
+                0..1,
+                &[Token::Keyword("MAIL FROM:")]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_mail_command_with_unicode() {
+        assert_eq!(
+            MailCommand::parse("MAIL FROM:<jdoe@😄.example>").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::SmtpUtf8Required,
+                "non-ASCII character requires SMTPUTF8",
+                16..20,
+                &[]
+            )
+        );
+        assert_eq!(
+            MailCommand::parse("MAIL FROM:<jdoe@machine.example> ENVID=😄").unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::SmtpUtf8Required,
+                "non-ASCII character requires SMTPUTF8",
+                39..43,
+                &[]
+            )
+        );
+        let command = MailCommand::parse("MAIL FROM:<jdoe@😄.example> SMTPUTF8").unwrap();
+        assert_eq!(
+            command.reverse_path().addr_spec().unwrap().domain(),
+            "😄.example"
+        );
+    }
+
+    #[test]
+    fn test_parse_rcpt_command() {
+        let command = RcptCommand::parse(
+            "RCPT TO:<jdoe@machine.example> NOTIFY=SUCCESS,FAILURE ORCPT=rfc822;jdoe@machine.example",
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            command.forward_path().addr_spec().unwrap().to_string(),
+            "jdoe@machine.example"
+        );
+        assert_eq!(
+            command.parameter("orcpt").unwrap().value(),
+            Some("rfc822;jdoe@machine.example")
+        );
+        assert_eq!(
+            command.to_string(),
+            "RCPT TO:<jdoe@machine.example> NOTIFY=SUCCESS,FAILURE ORCPT=rfc822;jdoe@machine.example"
+        );
+    }
+
+    #[test]
+    fn test_parse_rcpt_command_with_postmaster() {
+        let command = RcptCommand::parse("RCPT TO:<Postmaster>\r\n", false).unwrap();
+        assert_eq!(command.forward_path(), &SmtpPath::Postmaster);
+    }
+
+    #[test]
+    fn test_parse_rcpt_command_with_unicode() {
+        assert_eq!(
+            RcptCommand::parse("RCPT TO:<😄@machine.example>", false).unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::SmtpUtf8Required,
+                "non-ASCII character requires SMTPUTF8",
+                9..13,
+                &[]
+            )
+        );
+        assert!(RcptCommand::parse("RCPT TO:<😄@machine.example>", true).is_ok());
+    }
+
+    #[test]
+    fn test_requires_smtputf8() {
+        assert!(!SmtpPath::Null.requires_smtputf8());
+        for (path, expected) in [
+            ("<Postmaster>", false),
+            ("<jdoe@m\u{fc}nchen.de>", !cfg!(feature = "idna")),
+            ("<j\u{f6}rg@example.com>", true),
+            ("<\"j\u{f6}rg doe\"@example.com>", true),
+        ] {
+            assert_eq!(
+                SmtpPath::parse_forward_path(path)
+                    .unwrap()
+                    .requires_smtputf8(),
+                expected,
+                "{path:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn test_downgrade() {
+        assert_eq!(SmtpPath::Null.downgrade().unwrap(), SmtpPath::Null);
+        for path in ["<Postmaster>", "<jdoe@[192.0.2.1]>"] {
+            let path = SmtpPath::parse_forward_path(path).unwrap();
+            assert_eq!(path.downgrade().unwrap(), path);
+        }
+        assert_eq!(
+            SmtpPath::parse_forward_path("<\"jdoe\"@M\u{dc}NCHEN.de>")
+                .unwrap()
+                .downgrade()
+                .unwrap()
+                .to_string(),
+            "<jdoe@xn--mnchen-3ya.de>"
+        );
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn test_downgrade_errors() {
+        assert_eq!(
+            SmtpPath::parse_forward_path("<@a.example,@\u{2603}.example:jdoe@example.com>")
+                .unwrap()
+                .downgrade()
+                .unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::DisallowedCodePoint { found: '\u{2603}' },
+                "disallowed code point in domain",
+                13..16,
+                &[]
+            )
+        );
+        assert_eq!(
+            SmtpPath::parse_forward_path("<@a.example:jdoe@\u{2603}.example>")
+                .unwrap()
+                .downgrade()
+                .unwrap_err()
+                .span(),
+            17..20
+        );
+        assert_eq!(
+            SmtpPath::parse_forward_path("<@a.example:\"j\u{f6}rg doe\"@example.com>")
+                .unwrap()
+                .downgrade()
+                .unwrap_err(),
+            ParseError::new(
+                ParseErrorKind::SmtpUtf8Required,
+                "non-ASCII character requires SMTPUTF8",
+                14..16,
+                &[]
+            )
+        );
+    }
+}
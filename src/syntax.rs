@@ -0,0 +1,551 @@
+use std::{fmt, ops::Range};
+
+use super::{
+    parser::{is_not_phrase_atext, Parser},
+    AddrSpec, ParseError, ParserOptions,
+};
+
+/// The kind of a [`SyntaxNode`].
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum SyntaxKind {
+    /// The whole address specification, including surrounding CFWS.
+    AddrSpec,
+    /// The local part, excluding surrounding CFWS.
+    LocalPart,
+    /// The `@` between the local part and the domain.
+    At,
+    /// The domain, excluding surrounding CFWS.
+    Domain,
+    /// An atom, e.g. a label of a dot-atom.
+    Atom,
+    /// A period between two atoms.
+    Dot,
+    /// A quoted string including its quotes, e.g. `"john doe"`.
+    QuotedString,
+    /// A domain literal including its brackets, e.g. `[192.0.2.1]`.
+    DomainLiteral,
+    /// A comment including its parentheses, e.g. `(John Doe)`.
+    Comment,
+    /// A folding white space, i.e. white spaces and line breaks.
+    FoldingWhiteSpace,
+}
+
+/// A node of a [`SyntaxTree`].
+///
+/// Nodes only store byte offsets into the input; use [`SyntaxTree::text`] to
+/// get their text.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SyntaxNode {
+    kind: SyntaxKind,
+    start: usize,
+    end: usize,
+    children: Vec<SyntaxNode>,
+}
+
+impl SyntaxNode {
+    #[inline]
+    fn new(kind: SyntaxKind, span: Range<usize>, children: Vec<SyntaxNode>) -> Self {
+        Self {
+            kind,
+            start: span.start,
+            end: span.end,
+            children,
+        }
+    }
+
+    /// Returns the kind of the node.
+    #[inline]
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// Returns the byte range of the input covered by the node.
+    #[inline]
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the children of the node in input order.
+    ///
+    /// The children of a node cover its span without gaps.
+    #[inline]
+    pub fn children(&self) -> &[SyntaxNode] {
+        &self.children
+    }
+}
+
+/// Lossless concrete syntax tree of an address specification.
+///
+/// Unlike [`AddrSpec`], the tree preserves comments, folding white spaces,
+/// quoting and escapes together with their byte offsets, so printing the tree
+/// reproduces the input exactly. This allows rewriting parts of an address in
+/// place without touching the rest of the input.
+///
+/// # Examples
+///
+/// ```
+/// use addr_spec::{ParserOptions, SyntaxKind, SyntaxTree};
+///
+/// let mut options = ParserOptions::default();
+/// options.white_spaces = true;
+/// options.comments = true;
+///
+/// let input = "\"john\\ doe\" (John) @ example.com";
+/// let tree = SyntaxTree::parse_with(input, &options).unwrap();
+/// assert_eq!(tree.to_string(), input);
+/// assert_eq!(tree.text(tree.local_part()), "\"john\\ doe\"");
+/// assert_eq!(tree.local_part().children()[0].kind(), SyntaxKind::QuotedString);
+/// assert_eq!(
+///     tree.replace(tree.domain(), "example.org"),
+///     "\"john\\ doe\" (John) @ example.org"
+/// );
+/// assert_eq!(tree.addr_spec().local_part(), "john doe");
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SyntaxTree<'a> {
+    input: &'a str,
+    root: SyntaxNode,
+    addr_spec: AddrSpec,
+}
+
+impl<'a> SyntaxTree<'a> {
+    pub(crate) fn new(
+        input: &'a str,
+        addr_spec: AddrSpec,
+        local_part: Range<usize>,
+        domain: Range<usize>,
+    ) -> Self {
+        let mut children = lex(input, 0..local_part.start);
+        children.push(SyntaxNode::new(
+            SyntaxKind::LocalPart,
+            local_part.clone(),
+            lex(input, local_part.clone()),
+        ));
+        children.extend(lex(input, local_part.end..domain.start));
+        children.push(SyntaxNode::new(
+            SyntaxKind::Domain,
+            domain.clone(),
+            lex(input, domain.clone()),
+        ));
+        children.extend(lex(input, domain.end..input.len()));
+
+        Self {
+            input,
+            root: SyntaxNode::new(SyntaxKind::AddrSpec, 0..input.len(), children),
+            addr_spec,
+        }
+    }
+
+    /// Parses an address specification into a syntax tree using the default
+    /// options.
+    #[inline]
+    pub fn parse(input: &'a str) -> Result<Self, ParseError> {
+        Parser::new(input).parse_syntax_tree()
+    }
+
+    /// Parses an address specification into a syntax tree using the given
+    /// options.
+    #[inline]
+    pub fn parse_with(input: &'a str, options: &ParserOptions) -> Result<Self, ParseError> {
+        Parser::with_options(input, options).parse_syntax_tree()
+    }
+
+    /// Returns the parsed input.
+    #[inline]
+    pub fn input(&self) -> &'a str {
+        self.input
+    }
+
+    /// Returns the root node, i.e. the [`SyntaxKind::AddrSpec`] node.
+    #[inline]
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    /// Returns the [`SyntaxKind::LocalPart`] node.
+    #[inline]
+    pub fn local_part(&self) -> &SyntaxNode {
+        self.child(SyntaxKind::LocalPart)
+    }
+
+    /// Returns the [`SyntaxKind::Domain`] node.
+    #[inline]
+    pub fn domain(&self) -> &SyntaxNode {
+        self.child(SyntaxKind::Domain)
+    }
+
+    fn child(&self, kind: SyntaxKind) -> &SyntaxNode {
+        self.root
+            .children
+            .iter()
+            .find(|node| node.kind == kind)
+            .unwrap()
+    }
+
+    /// Returns the text of the node.
+    #[inline]
+    pub fn text(&self, node: &SyntaxNode) -> &'a str {
+        &self.input[node.span()]
+    }
+
+    /// Returns the input with the text of the node replaced and everything
+    /// else left untouched.
+    ///
+    /// The replacement is not validated.
+    pub fn replace(&self, node: &SyntaxNode, replacement: &str) -> String {
+        [
+            &self.input[..node.start],
+            replacement,
+            &self.input[node.end..],
+        ]
+        .concat()
+    }
+
+    /// Returns the decoded comments in input order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use addr_spec::{CommentPosition, ParserOptions, SyntaxTree};
+    ///
+    /// let mut options = ParserOptions::default();
+    /// options.white_spaces = true;
+    /// options.comments = true;
+    ///
+    /// let tree = SyntaxTree::parse_with("jdoe@example.com (John \\(Doe\\))", &options).unwrap();
+    /// let comments = tree.comments();
+    /// assert_eq!(comments[0].text(), "John (Doe)");
+    /// assert_eq!(comments[0].position(), CommentPosition::AfterDomain);
+    /// ```
+    pub fn comments(&self) -> Vec<Comment> {
+        let mut comments = Vec::new();
+        let mut position = CommentPosition::BeforeLocalPart;
+        for node in self.root.children() {
+            match node.kind {
+                SyntaxKind::LocalPart => {
+                    self.collect_comments(node, CommentPosition::InLocalPart, &mut comments);
+                    position = CommentPosition::BeforeAt;
+                }
+                SyntaxKind::At => position = CommentPosition::AfterAt,
+                SyntaxKind::Domain => {
+                    self.collect_comments(node, CommentPosition::InDomain, &mut comments);
+                    position = CommentPosition::AfterDomain;
+                }
+                _ => self.collect_comments(node, position, &mut comments),
+            }
+        }
+        comments
+    }
+
+    fn collect_comments(
+        &self,
+        node: &SyntaxNode,
+        position: CommentPosition,
+        comments: &mut Vec<Comment>,
+    ) {
+        if node.kind == SyntaxKind::Comment {
+            comments.push(Comment {
+                text: decode_comment(self.text(node)),
+                position,
+                start: node.start,
+                end: node.end,
+            });
+        }
+        for child in node.children() {
+            self.collect_comments(child, position, comments);
+        }
+    }
+
+    /// Returns the parsed address specification.
+    #[inline]
+    pub fn addr_spec(&self) -> &AddrSpec {
+        &self.addr_spec
+    }
+
+    /// Returns the parsed address specification.
+    #[inline]
+    pub fn into_addr_spec(self) -> AddrSpec {
+        self.addr_spec
+    }
+}
+
+impl From<SyntaxTree<'_>> for AddrSpec {
+    #[inline]
+    fn from(tree: SyntaxTree<'_>) -> Self {
+        tree.into_addr_spec()
+    }
+}
+
+impl fmt::Display for SyntaxTree<'_> {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.text(&self.root))
+    }
+}
+
+/// The position of a [`Comment`] relative to the parts of an address
+/// specification.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum CommentPosition {
+    /// Before the local part, e.g. `(John Doe) jdoe@example.com`.
+    BeforeLocalPart,
+    /// Inside an obsolete local part, e.g. `john(Doe).doe@example.com`.
+    InLocalPart,
+    /// Between the local part and the `@`, e.g. `jdoe(John Doe)@example.com`.
+    BeforeAt,
+    /// Between the `@` and the domain, e.g. `jdoe@(John Doe)example.com`.
+    AfterAt,
+    /// Inside an obsolete domain, e.g. `jdoe@example(John Doe).com`.
+    InDomain,
+    /// After the domain, e.g. `jdoe@example.com (John Doe)`.
+    AfterDomain,
+}
+
+/// A decoded comment of an address specification.
+///
+/// The text excludes the outer parentheses, quoted pairs are unescaped and
+/// folding white spaces are unfolded. Nested comments are kept verbatim.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Comment {
+    text: String,
+    position: CommentPosition,
+    start: usize,
+    end: usize,
+}
+
+impl Comment {
+    /// Returns the decoded text of the comment.
+    #[inline]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the position of the comment.
+    #[inline]
+    pub fn position(&self) -> CommentPosition {
+        self.position
+    }
+
+    /// Returns the byte range of the comment in the input, including its
+    /// parentheses.
+    #[inline]
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the decoded text of the comment.
+    #[inline]
+    pub fn into_text(self) -> String {
+        self.text
+    }
+}
+
+impl fmt::Display for Comment {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.text())
+    }
+}
+
+/// Decodes an already validated comment including its parentheses.
+fn decode_comment(comment: &str) -> String {
+    let mut text = String::with_capacity(comment.len());
+    let mut chars = comment[1..comment.len() - 1].chars();
+    while let Some(chr) = chars.next() {
+        match chr {
+            '\\' => text.extend(chars.next()),
+            '\r' if chars.as_str().starts_with('\n') => {
+                chars.next();
+            }
+            chr => text.push(chr),
+        }
+    }
+    text
+}
+
+/// Splits the range of an already validated input into leaf nodes.
+fn lex(input: &str, range: Range<usize>) -> Vec<SyntaxNode> {
+    let mut nodes = Vec::new();
+    let mut index = range.start;
+    while index < range.end {
+        let rest = &input[index..range.end];
+        let (kind, size) = match rest.as_bytes()[0] {
+            b'@' => (SyntaxKind::At, 1),
+            b'.' => (SyntaxKind::Dot, 1),
+            b'"' => (SyntaxKind::QuotedString, quoted_string_len(rest)),
+            b'[' => (
+                SyntaxKind::DomainLiteral,
+                rest.find(']').map_or(rest.len(), |size| size + 1),
+            ),
+            b'(' => (SyntaxKind::Comment, comment_len(rest)),
+            b' ' | b'\t' | b'\r' | b'\n' => (
+                SyntaxKind::FoldingWhiteSpace,
+                rest.find(|chr| !matches!(chr, ' ' | '\t' | '\r' | '\n'))
+                    .unwrap_or(rest.len()),
+            ),
+            _ => (
+                SyntaxKind::Atom,
+                rest.find(is_not_phrase_atext)
+                    .unwrap_or(rest.len())
+                    .max(rest.chars().next().map_or(1, char::len_utf8)),
+            ),
+        };
+        nodes.push(SyntaxNode::new(kind, index..index + size, Vec::new()));
+        index += size;
+    }
+    nodes
+}
+
+fn quoted_string_len(input: &str) -> usize {
+    let mut chars = input.char_indices().skip(1);
+    while let Some((index, chr)) = chars.next() {
+        match chr {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return index + 1,
+            _ => {}
+        }
+    }
+    input.len()
+}
+
+fn comment_len(input: &str) -> usize {
+    let mut level = 0usize;
+    let mut chars = input.char_indices();
+    while let Some((index, chr)) = chars.next() {
+        match chr {
+            '\\' => {
+                chars.next();
+            }
+            '(' => level += 1,
+            ')' => {
+                level -= 1;
+                if level == 0 {
+                    return index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    input.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Profile;
+
+    fn kinds(nodes: &[SyntaxNode]) -> Vec<SyntaxKind> {
+        nodes.iter().map(SyntaxNode::kind).collect()
+    }
+
+    #[test]
+    fn test_syntax_tree_round_trip() {
+        for input in [
+            "jdoe@machine.example",
+            " (a (nested) comment) jdoe @ [ 192.0.2.1 ] (\\)) \r\n ",
+            "\"j\\\"doe\\ \"@example.com",
+            "\"john\r\n doe\"@[IPv6:2001:db8::1]",
+        ] {
+            let tree = SyntaxTree::parse_with(input, &Profile::Rfc5322.into()).unwrap();
+            assert_eq!(tree.to_string(), input);
+            assert_eq!(tree.root().span(), 0..input.len());
+        }
+    }
+
+    #[test]
+    fn test_syntax_tree_nodes() {
+        let input = "(x) jdoe.x @machine.example ";
+        let tree = SyntaxTree::parse_with(input, &Profile::Rfc5322.into()).unwrap();
+        assert_eq!(
+            kinds(tree.root().children()),
+            [
+                SyntaxKind::Comment,
+                SyntaxKind::FoldingWhiteSpace,
+                SyntaxKind::LocalPart,
+                SyntaxKind::FoldingWhiteSpace,
+                SyntaxKind::At,
+                SyntaxKind::Domain,
+                SyntaxKind::FoldingWhiteSpace,
+            ]
+        );
+        assert_eq!(
+            kinds(tree.local_part().children()),
+            [SyntaxKind::Atom, SyntaxKind::Dot, SyntaxKind::Atom]
+        );
+        assert_eq!(tree.local_part().span(), 4..10);
+        assert_eq!(tree.text(tree.domain()), "machine.example");
+        assert_eq!(tree.text(&tree.domain().children()[2]), "example");
+    }
+
+    #[test]
+    fn test_syntax_tree_with_obsolete_syntax() {
+        let mut options = ParserOptions::new();
+        options.obsolete = true;
+        let input = "\"john\" . doe @ example . com";
+        let tree = SyntaxTree::parse_with(input, &options).unwrap();
+        assert_eq!(tree.to_string(), input);
+        assert_eq!(tree.text(tree.local_part()), "\"john\" . doe");
+        assert_eq!(tree.text(tree.domain()), "example . com");
+        assert_eq!(
+            kinds(tree.local_part().children()),
+            [
+                SyntaxKind::QuotedString,
+                SyntaxKind::FoldingWhiteSpace,
+                SyntaxKind::Dot,
+                SyntaxKind::FoldingWhiteSpace,
+                SyntaxKind::Atom,
+            ]
+        );
+        assert_eq!(tree.addr_spec().to_string(), "john.doe@example.com");
+    }
+
+    #[test]
+    fn test_syntax_tree_replace_domain() {
+        let input = "jdoe (John Doe) @ machine.example (work)";
+        let tree = SyntaxTree::parse_with(input, &Profile::Rfc5322.into()).unwrap();
+        assert_eq!(
+            tree.replace(tree.domain(), "example.org"),
+            "jdoe (John Doe) @ example.org (work)"
+        );
+    }
+
+    #[test]
+    fn test_syntax_tree_comments() {
+        let mut options = ParserOptions::from(Profile::Rfc5322);
+        options.obsolete = true;
+        let tree = SyntaxTree::parse_with(
+            "(a) john(b).doe (c) @ (d\\)) example . (e\r\n f) com (g (h))",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            tree.comments()
+                .iter()
+                .map(|comment| (comment.text(), comment.position()))
+                .collect::<Vec<_>>(),
+            [
+                ("a", CommentPosition::BeforeLocalPart),
+                ("b", CommentPosition::InLocalPart),
+                ("c", CommentPosition::BeforeAt),
+                ("d)", CommentPosition::AfterAt),
+                ("e f", CommentPosition::InDomain),
+                ("g (h)", CommentPosition::AfterDomain),
+            ]
+        );
+        assert_eq!(tree.comments()[0].span(), 0..3);
+    }
+
+    #[test]
+    fn test_syntax_tree_into_addr_spec() {
+        let tree =
+            SyntaxTree::parse_with("\"jdoe\"@machine.example", &Profile::Rfc5322.into()).unwrap();
+        assert_eq!(
+            AddrSpec::from(tree),
+            AddrSpec::new("jdoe", "machine.example").unwrap()
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 #[cfg(feature = "normalization")]
 pub fn normalize<S>(value: S) -> String
 where
@@ -7,6 +9,16 @@ where
     value.as_ref().nfc().collect::<String>()
 }
 
+/// Normalizes the value, avoiding an allocation if it is already normalized.
+#[cfg(feature = "normalization")]
+pub fn normalize_cow(value: Cow<'_, str>) -> Cow<'_, str> {
+    use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+    if is_nfc_quick(value.chars()) == IsNormalized::Yes {
+        return value;
+    }
+    Cow::Owned(value.nfc().collect())
+}
+
 #[cfg(not(feature = "normalization"))]
 pub fn normalize<S>(value: S) -> String
 where
@@ -14,3 +26,99 @@ where
 {
     value.into()
 }
+
+/// Returns the length of the label in octets after conversion into an A-label.
+#[cfg(feature = "idna")]
+pub fn a_label_len(label: &str) -> usize {
+    if label.is_ascii() {
+        return label.len();
+    }
+    idna::punycode::encode_str(label).map_or(usize::MAX, |label| "xn--".len() + label.len())
+}
+
+/// Returns the length of the label in octets. Without IDNA support, U-labels
+/// are measured by their UTF-8 length instead.
+#[cfg(not(feature = "idna"))]
+pub fn a_label_len(label: &str) -> usize {
+    label.len()
+}
+
+/// Maps the domain for case-insensitive comparison, i.e. converts it into its
+/// lowercase ASCII form as defined in UTS #46. Domains that cannot be
+/// converted are lowercased instead.
+#[cfg(feature = "idna")]
+pub fn fold_domain(domain: &str) -> Cow<'_, str> {
+    if !domain
+        .bytes()
+        .any(|byte| byte.is_ascii_uppercase() || !byte.is_ascii())
+    {
+        return Cow::Borrowed(domain);
+    }
+    Cow::Owned(idna::domain_to_ascii(domain).unwrap_or_else(|_| domain.to_lowercase()))
+}
+
+/// Maps the domain for case-insensitive comparison, i.e. lowercases it and
+/// replaces the Unicode full stops by periods.
+#[cfg(not(feature = "idna"))]
+pub fn fold_domain(domain: &str) -> Cow<'_, str> {
+    if !domain
+        .bytes()
+        .any(|byte| byte.is_ascii_uppercase() || !byte.is_ascii())
+    {
+        return Cow::Borrowed(domain);
+    }
+    Cow::Owned(
+        domain
+            .chars()
+            .map(|chr| match chr {
+                '\u{3002}' | '\u{ff0e}' | '\u{ff61}' => '.',
+                chr => chr,
+            })
+            .flat_map(char::to_lowercase)
+            .collect(),
+    )
+}
+
+/// Returns the confusable skeleton of the value as defined in [UTS
+/// #39](https://www.unicode.org/reports/tr39/#Confusable_Detection).
+#[cfg(feature = "security")]
+pub fn skeleton(value: &str) -> String {
+    unicode_security::skeleton(value).collect()
+}
+
+/// Returns the domain in lowercase Unicode form, i.e. with A-labels converted
+/// into U-labels if possible.
+#[cfg(feature = "security")]
+pub fn unicode_domain(domain: &str) -> String {
+    #[cfg(feature = "idna")]
+    if let Ok(domain) = super::idn::domain_to_unicode(domain) {
+        return domain.into_owned();
+    }
+    domain.to_lowercase()
+}
+
+/// Returns whether the character is a default-ignorable code point, i.e. is
+/// invisible when not supported, such as ZWJ, the soft hyphen or bidi
+/// controls.
+pub const fn is_default_ignorable(chr: char) -> bool {
+    matches!(
+        chr,
+        '\u{ad}'
+            | '\u{34f}'
+            | '\u{61c}'
+            | '\u{115f}'..='\u{1160}'
+            | '\u{17b4}'..='\u{17b5}'
+            | '\u{180b}'..='\u{180f}'
+            | '\u{200b}'..='\u{200f}'
+            | '\u{202a}'..='\u{202e}'
+            | '\u{2060}'..='\u{206f}'
+            | '\u{3164}'
+            | '\u{fe00}'..='\u{fe0f}'
+            | '\u{feff}'
+            | '\u{ffa0}'
+            | '\u{fff0}'..='\u{fff8}'
+            | '\u{1bca0}'..='\u{1bca3}'
+            | '\u{1d173}'..='\u{1d17a}'
+            | '\u{e0000}'..='\u{e0fff}'
+    )
+}